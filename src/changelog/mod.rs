@@ -0,0 +1,108 @@
+use anyhow::Result;
+
+use crate::{git::Git, lint::parse_header};
+
+#[cfg(test)]
+mod test;
+
+const TITLE: &str = "# Changelog";
+const BREAKING_TITLE: &str = "Breaking Changes";
+const OTHER_TITLE: &str = "Other Changes";
+/// conventional commit types with their section titles, in order
+const TYPE_TITLES: [(&str, &str); 5] = [
+    ("feat", "Features"),
+    ("fix", "Bug Fixes"),
+    ("perf", "Performance"),
+    ("refactor", "Refactoring"),
+    ("docs", "Documentation"),
+];
+
+/// Changelog section of commits between from and to, from defaults to the latest tag before to.
+pub fn generate_section(from: Option<String>, to: &str, version: &str) -> Result<String> {
+    let from = match from {
+        Some(from_v) => Some(from_v),
+        None => Git::get_latest_tag(&format!("{}^", to))?,
+    };
+
+    let mut messages = Vec::<String>::new();
+    for commit in Git::list_commits(from.as_deref(), to)?.iter().rev() {
+        messages.push(Git::get_commit_message(commit)?);
+    }
+    let date = Git::get_commit_date(to)?;
+    Ok(render_section(version, &date, &messages))
+}
+
+/// Markdown section of messages grouped by conventional commit type, merge commits are skipped.
+pub fn render_section(version: &str, date: &str, messages: &[String]) -> String {
+    let mut groups = Vec::<(&str, Vec<String>)>::new();
+    let mut push =
+        |title: &'static str, entry: String| match groups.iter_mut().find(|x| x.0 == title) {
+            Some(group) => group.1.push(entry),
+            None => groups.push((title, vec![entry])),
+        };
+
+    for message in messages.iter() {
+        let header = message.lines().next().unwrap_or_default();
+        if header.starts_with("Merge ") {
+            continue;
+        }
+
+        match parse_header(header) {
+            None => push(OTHER_TITLE, header.to_string()),
+            Some(header_v) => {
+                let entry = match &header_v.scope {
+                    None => header_v.subject.clone(),
+                    Some(scope) => format!("**{}:** {}", scope, header_v.subject),
+                };
+                if header_v.breaking || message.contains("BREAKING CHANGE") {
+                    push(BREAKING_TITLE, entry.clone());
+                }
+                let title = TYPE_TITLES
+                    .iter()
+                    .find(|x| x.0 == header_v.commit_type)
+                    .map(|x| x.1)
+                    .unwrap_or(OTHER_TITLE);
+                push(title, entry);
+            }
+        }
+    }
+
+    // -- render in order of titles --
+    let mut section = format!("## {} ({})\n", version, date);
+    let titles = [BREAKING_TITLE]
+        .into_iter()
+        .chain(TYPE_TITLES.iter().map(|x| x.1))
+        .chain([OTHER_TITLE]);
+    for title in titles {
+        if let Some(group) = groups.iter().find(|x| x.0 == title) {
+            section.push_str(&format!("\n### {}\n\n", title));
+            for entry in group.1.iter() {
+                section.push_str(&format!("- {}\n", entry));
+            }
+        }
+    }
+    section
+}
+
+/// Insert section above the latest release of changelog, below its title and intro.
+/// The title is created if changelog has none.
+pub fn insert_section(changelog: &str, section: &str) -> String {
+    let mut position = changelog.len();
+    let mut offset = 0;
+    for line in changelog.split_inclusive('\n') {
+        if line.starts_with("## ") {
+            position = offset;
+            break;
+        }
+        offset += line.len();
+    }
+
+    let head = match changelog[..position].trim() {
+        "" => TITLE,
+        head_v => head_v,
+    };
+    format!("{}\n\n{}\n{}", head, section, &changelog[position..])
+        .trim_end()
+        .to_string()
+        + "\n"
+}
@@ -0,0 +1,38 @@
+use super::*;
+
+#[test]
+fn render_section_t() {
+    let messages = [
+        "feat(ui): login page",
+        "fix: crash on start",
+        "Merge branch 'feature/login' into dev",
+        "feat!: drop v1 api",
+        "chore: bump deps",
+    ]
+    .map(|x| x.to_string());
+    assert_eq!(
+        render_section("1.2.0", "2024-01-31", &messages),
+        "## 1.2.0 (2024-01-31)\n\n### Breaking Changes\n\n- drop v1 api\n\n### Features\n\n- **ui:** login page\n- drop v1 api\n\n### Bug Fixes\n\n- crash on start\n\n### Other Changes\n\n- bump deps\n"
+    );
+}
+
+#[test]
+fn insert_section_t() {
+    let section = "## 1.1.0 (2024-01-31)\n\n### Bug Fixes\n\n- crash\n";
+    assert_eq!(
+        insert_section("", section),
+        format!("# Changelog\n\n{}", section)
+    );
+    assert_eq!(
+        insert_section("# Changelog\n\n## 1.0.0 (2024-01-01)\n", section),
+        format!("# Changelog\n\n{}\n## 1.0.0 (2024-01-01)\n", section)
+    );
+    assert_eq!(
+        insert_section("# History\n\nNotable changes.\n\n## 1.0.0\n", section),
+        format!("# History\n\nNotable changes.\n\n{}\n## 1.0.0\n", section)
+    );
+    assert_eq!(
+        insert_section("# History\n\nNotable changes.\n", section),
+        format!("# History\n\nNotable changes.\n\n{}", section)
+    );
+}
@@ -19,38 +19,179 @@ pub enum Command {
         /// input full branch name if no branch type input
         branch_name: String,
         branch_type: Option<String>,
+        /// fill a placeholder of create other than {NAME}, prompted if missing
+        #[arg(long = "var", value_name = "KEY=VALUE")]
+        vars: Vec<String>,
+        /// version line replacing {BASE} of the branch type, recorded on start
+        #[arg(long)]
+        base: Option<String>,
+        /// ticket ID if it is not in the branch name
+        #[arg(long)]
+        ticket: Option<String>,
+        /// stash uncommitted changes before and restore them after
+        #[arg(long)]
+        autostash: bool,
     },
     /// finish a task
     Finish {
         /// input full branch name if no branch type input
         branch_name: String,
         branch_type: Option<String>,
+        /// fill a placeholder of create other than {NAME}, prompted if missing
+        #[arg(long = "var", value_name = "KEY=VALUE")]
+        vars: Vec<String>,
+        /// version line replacing {BASE} of the branch type, recorded on start
+        #[arg(long)]
+        base: Option<String>,
+        /// stash uncommitted changes before and restore them after
+        #[arg(long)]
+        autostash: bool,
+        /// resolve targets in temporary worktrees instead of switching the current checkout
+        #[arg(long)]
+        worktree: bool,
+        /// delete the branch if its pull requests are merged
+        #[arg(long)]
+        check_pr: bool,
     },
     /// drop a task
     Drop {
         /// input full branch name if no branch type input
         branch_name: String,
         branch_type: Option<String>,
+        /// fill a placeholder of create other than {NAME}, prompted if missing
+        #[arg(long = "var", value_name = "KEY=VALUE")]
+        vars: Vec<String>,
+        /// version line replacing {BASE} of the branch type, recorded on start
+        #[arg(long)]
+        base: Option<String>,
+        /// stash uncommitted changes before and restore them after
+        #[arg(long)]
+        autostash: bool,
+        /// drop unmerged commits without confirmation
+        #[arg(long)]
+        force: bool,
+        /// keep the branch tip as archive/<branch> tag or refs/git-flow/dropped/<branch>
+        #[arg(long)]
+        archive: Option<ArchiveKind>,
+    },
+    /// restore a dropped task from its archive
+    Restore {
+        /// full branch name
+        branch_name: String,
     },
     /// track a task
     Track {
         /// input full branch name if no branch type input
         branch_name: String,
         branch_type: Option<String>,
+        /// fill a placeholder of create other than {NAME}, prompted if missing
+        #[arg(long = "var", value_name = "KEY=VALUE")]
+        vars: Vec<String>,
+    },
+    /// print the changelog section of commits between from and to
+    Changelog {
+        /// defaults to the latest tag before to
+        #[arg(long)]
+        from: Option<String>,
+        #[arg(long, default_value = "HEAD")]
+        to: String,
+    },
+    /// show task branches grouped by ticket
+    Status,
+    /// undo the last git-flow operations
+    Undo {
+        /// number of operations to undo
+        #[arg(long, default_value_t = 1)]
+        steps: usize,
     },
     /// sync branches
     Sync {
-        target: SyncTarget,
+        target: SyncAction,
         /// default is increment
         strategy: Option<SyncStrategy>,
+        /// delete redundant branches without confirmation
+        #[arg(long)]
+        yes: bool,
+        /// only sync branches matching this regex
+        #[arg(long, value_name = "REGEX")]
+        only: Option<String>,
+        /// only sync task branches of these branch types
+        #[arg(long, value_delimiter = ',')]
+        types: Vec<String>,
+        /// remote repo to sync with, instead of the configured one
+        #[arg(long)]
+        remote: Option<String>,
+    },
+    /// delete task branches merged into all targets or without recent commits
+    Prune {
+        /// also prune branches without commits for this many days
+        #[arg(long)]
+        days: Option<u64>,
+        /// remote repo to delete branches on, default is sync.push_to or the only remote repo
+        #[arg(long)]
+        remote: Option<String>,
+        /// delete without confirmation
+        #[arg(long)]
+        yes: bool,
+        /// run after_finish hooks of merged branches
+        #[arg(long)]
+        run_hooks: bool,
     },
     /// list avaliable branch types
     List,
-    /// check config
-    Check { file_path: PathBuf },
+    /// check the config file, or all existing config files
+    Check {
+        file_path: Option<PathBuf>,
+        /// also check branches and hook commands against the repo
+        #[arg(long)]
+        repo: bool,
+    },
+    /// inspect and edit config
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum ConfigAction {
+    /// print the effective config and the file it is read from
+    Show,
+    /// print config paths in lookup order
+    Path,
+    /// print the JSON Schema of config, for editor completion and validation
+    Schema,
+    /// add a branch type
+    AddType {
+        name: String,
+        /// e.g. "feature/{NAME}"
+        #[arg(long)]
+        create: String,
+        #[arg(long)]
+        from: String,
+        /// target branch regex and strategy, strategy defaults to merge
+        #[arg(long, value_name = "BRANCH[:STRATEGY]")]
+        to: Vec<String>,
+    },
+    /// remove a branch type
+    RemoveType { name: String },
+    /// set a field of a branch type, value is parsed as TOML or taken as a string
+    Set {
+        /// <type>.<field>, e.g. feature.from or hotfix.tag.branch
+        key: String,
+        value: String,
+    },
 }
 
 #[derive(Debug, Clone, ValueEnum)]
+pub enum SyncAction {
+    Local,
+    Remote,
+    /// set upstream of local branches to their remote counterparts
+    RepairTracking,
+}
+
+#[derive(Debug, Clone)]
 pub enum SyncTarget {
     Local,
     Remote,
@@ -61,3 +202,9 @@ pub enum SyncStrategy {
     Override,
     Increment,
 }
+
+#[derive(Debug, Clone, ValueEnum)]
+pub enum ArchiveKind {
+    Tag,
+    Ref,
+}
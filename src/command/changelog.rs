@@ -0,0 +1,12 @@
+use crate::{changelog::generate_section, echo::Echo};
+
+pub fn print_changelog(from: Option<String>, to: &str) {
+    let version = match to {
+        "HEAD" => "Unreleased",
+        _ => to,
+    };
+    match generate_section(from, to, version) {
+        Err(err) => Echo::error(err.to_string()),
+        Ok(section) => print!("{}", section),
+    }
+}
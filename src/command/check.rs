@@ -1,13 +1,82 @@
-use std::path::PathBuf;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
-use crate::{config::read::read_config, echo::Echo};
+use crate::{
+    config::{
+        diagnostic::diagnose,
+        inspect::{inspect_config, Repo},
+        path::get_config_path_list,
+        read::parse_config,
+    },
+    echo::Echo,
+};
 
-pub fn check_config(config_path: PathBuf) {
-    match read_config(Some(config_path)) {
-        Ok(_) => Echo::success("config is valid"),
+/// Check the given config file, or all existing config files in lookup order.
+/// With repo, valid config is also checked against branches and commands of the repo.
+pub fn check_config(config_path: Option<PathBuf>, repo: bool) {
+    let path_list = match config_path {
+        Some(config_path_v) => vec![config_path_v],
+        None => match get_config_path_list() {
+            Err(err) => {
+                Echo::error(err.to_string());
+                return;
+            }
+            Ok(path_list_v) => path_list_v.into_iter().filter(|x| x.is_file()).collect(),
+        },
+    };
+    if path_list.is_empty() {
+        Echo::error("config file is not found");
+        return;
+    }
+
+    for path in path_list.iter() {
+        let text = match fs::read_to_string(path) {
+            Err(err) => {
+                Echo::error(format!("unable to read {}: {}", path.display(), err));
+                continue;
+            }
+            Ok(text_v) => text_v,
+        };
+
+        match diagnose(&text) {
+            None => Echo::success(format!("config {} is valid", path.display())),
+            Some(diagnostic) => {
+                Echo::error(format!("config {} is invalid", path.display()));
+                eprintln!("\n{}\n", diagnostic.render(path, &text));
+                continue;
+            }
+        }
+
+        if repo {
+            check_repo(path, &text);
+        }
+    }
+}
+
+fn check_repo(path: &Path, text: &str) {
+    let result = parse_config(text).and_then(|config| Ok((config, Repo::load()?)));
+    let (config, repo) = match result {
         Err(err) => {
-            Echo::error("config is invalid");
-            eprintln!("\n{:?}", err);
+            Echo::error(err.to_string());
+            return;
         }
+        Ok(config_repo) => config_repo,
+    };
+
+    let diagnostics = inspect_config(&config, &repo);
+    if diagnostics.is_empty() {
+        Echo::success(format!("config {} fits the repo", path.display()));
+        return;
+    }
+    Echo::warning(format!(
+        "config {} has {} problems in the repo",
+        path.display(),
+        diagnostics.len()
+    ));
+    for diagnostic in diagnostics.iter() {
+        eprintln!("\n{}", diagnostic.render(path, text));
     }
+    eprintln!();
 }
@@ -0,0 +1,135 @@
+use std::{fs, path::PathBuf};
+
+use anyhow::{bail, Result};
+
+use crate::{
+    config::{
+        edit::{add_branch_type, remove_branch_type, set_branch_type_field},
+        path::get_config_path_list,
+        read::{find_config_path, parse_config},
+        schema::config_schema,
+    },
+    echo::Echo,
+};
+
+pub fn show_config(config_path: Option<PathBuf>) {
+    let path = match find_config_path(config_path) {
+        Err(err) => {
+            Echo::error(err.to_string());
+            return;
+        }
+        Ok(None) => {
+            Echo::error("config file is not found");
+            return;
+        }
+        Ok(Some(path_v)) => path_v,
+    };
+
+    let result = fs::read_to_string(&path)
+        .map_err(anyhow::Error::from)
+        .and_then(|x| parse_config(&x))
+        .and_then(|x| Ok(toml::to_string_pretty(&x)?));
+    match result {
+        Err(err) => {
+            Echo::error(format!("{}: {:#}", path.display(), err));
+        }
+        Ok(text) => {
+            Echo::info(format!("config is read from {}", path.display()));
+            print!("{}", text);
+        }
+    }
+}
+
+pub fn show_config_paths(config_path: Option<PathBuf>) {
+    let path_list = match config_path {
+        Some(config_path_v) => vec![config_path_v],
+        None => match get_config_path_list() {
+            Err(err) => {
+                Echo::error(err.to_string());
+                return;
+            }
+            Ok(path_list_v) => path_list_v,
+        },
+    };
+
+    let mut used = false;
+    for path in path_list.iter() {
+        let state = match path.is_file() {
+            false => "not found",
+            true if used => "shadowed",
+            true => {
+                used = true;
+                "used"
+            }
+        };
+        println!("{} ({})", path.display(), state);
+    }
+}
+
+pub fn show_schema() {
+    print!("{}", config_schema());
+}
+
+pub fn add_type(name: &str, create: &str, from: &str, to: &[String], config_path: Option<PathBuf>) {
+    edit_config(config_path, true, |text| {
+        add_branch_type(text, name, create, from, to)
+    });
+}
+
+pub fn remove_type(name: &str, config_path: Option<PathBuf>) {
+    edit_config(config_path, false, |text| remove_branch_type(text, name));
+}
+
+pub fn set_field(key: &str, value: &str, config_path: Option<PathBuf>) {
+    edit_config(config_path, false, |text| {
+        set_branch_type_field(text, key, value)
+    });
+}
+
+/// Edit the config file in use, the result is validated before it is written.
+/// If no config file exists and create is true, the local config file is created.
+fn edit_config(
+    config_path: Option<PathBuf>,
+    create: bool,
+    edit: impl FnOnce(&str) -> Result<String>,
+) {
+    let path = match get_edit_path(config_path, create) {
+        Err(err) => {
+            Echo::error(err.to_string());
+            return;
+        }
+        Ok(path_v) => path_v,
+    };
+
+    let result = fs::read_to_string(&path)
+        .or_else(|err| match create && !path.exists() {
+            true => Ok(String::new()),
+            false => Err(err),
+        })
+        .map_err(anyhow::Error::from)
+        .and_then(|x| edit(&x))
+        .and_then(|x| parse_config(&x).map(|_| x));
+    let text = match result {
+        Err(err) => {
+            Echo::error(format!("config is not changed: {:#}", err));
+            return;
+        }
+        Ok(text_v) => text_v,
+    };
+
+    match fs::write(&path, text) {
+        Err(err) => Echo::error(err.to_string()),
+        Ok(_) => Echo::success(format!("update {}", path.display())),
+    }
+}
+
+fn get_edit_path(config_path: Option<PathBuf>, create: bool) -> Result<PathBuf> {
+    match config_path {
+        Some(config_path_v) if create => Ok(config_path_v),
+        _ => match find_config_path(config_path)? {
+            Some(path) => Ok(path),
+            None if create => Ok(get_config_path_list()?.remove(0)),
+            None => bail!("config file is not found"),
+        },
+    }
+}
@@ -1,6 +1,21 @@
-use crate::{config::definition::BranchType, echo::Echo, git::Git, utils::run_hook};
+use crate::{
+    cli::ArchiveKind,
+    config::definition::BranchType,
+    echo::Echo,
+    git::Git,
+    journal::Journal,
+    utils::{confirm, run_hook},
+};
 
-pub fn drop_task(branch_name: String, branch_type: BranchType) {
+pub const ARCHIVE_TAG_PREFIX: &str = "refs/tags/archive/";
+pub const ARCHIVE_REF_PREFIX: &str = "refs/git-flow/dropped/";
+
+pub fn drop_task(
+    branch_name: String,
+    branch_type: BranchType,
+    force: bool,
+    archive: Option<ArchiveKind>,
+) {
     // -- validate branches --
     let branches = match Git::get_local_branches() {
         Err(err) => {
@@ -14,11 +29,66 @@ pub fn drop_task(branch_name: String, branch_type: BranchType) {
         return;
     }
 
+    // -- check unmerged commits --
+    let commits = match Git::diff_commits(&branch_name, &branch_type.from) {
+        Err(err) => {
+            Echo::error(err.to_string());
+            return;
+        }
+        Ok(commits_v) => commits_v,
+    };
+    if !commits.is_empty() && archive.is_none() {
+        Echo::warning(format!(
+            "these commits on {} are not merged into {} and will be lost:",
+            &branch_name, &branch_type.from
+        ));
+        for commit in commits.iter() {
+            match Git::get_commit_summary(commit) {
+                Err(_) => println!("  {}", commit),
+                Ok(summary) => println!("  {}", summary),
+            }
+        }
+        if !force && !confirm("drop them anyway?") {
+            return;
+        }
+    }
+
+    // -- check archive --
+    let archive_ref = archive.map(|x| match x {
+        ArchiveKind::Tag => format!("{}{}", ARCHIVE_TAG_PREFIX, &branch_name),
+        ArchiveKind::Ref => format!("{}{}", ARCHIVE_REF_PREFIX, &branch_name),
+    });
+    if let Some(ref_name) = archive_ref.as_ref().filter(|x| Git::get_commit(x).is_ok()) {
+        Echo::error(format!(
+            "{} exists, restore or delete it before archiving branch {} again",
+            ref_name, &branch_name
+        ));
+        return;
+    }
+
     // -- run before drop hook --
     if run_hook(branch_type.before_drop.clone(), &branch_name, &branch_type).is_err() {
         return;
     }
 
+    // -- archive branch --
+    if let Some(ref_name) = &archive_ref {
+        // create fails if ref_name is created meanwhile, so nothing is overwritten
+        let finish = Echo::progress(format!("archive branch {} as {}", &branch_name, ref_name));
+        let result = Git::get_commit(&branch_name).and_then(|x| Git::create_ref(ref_name, &x));
+        match result {
+            Err(err) => {
+                finish(false, &err.to_string());
+                return;
+            }
+            Ok(_) => finish(
+                true,
+                &format!("archive branch {} as {}", &branch_name, ref_name),
+            ),
+        }
+        Journal::record_ref(ref_name, None);
+    }
+
     // -- switch to source branch --
     let finish = Echo::progress(format!("switch to branch {}", &branch_type.from));
     match Git::switch(&branch_type.from) {
@@ -30,6 +100,8 @@ pub fn drop_task(branch_name: String, branch_type: BranchType) {
     }
 
     // -- delete branch --
+    let before = Git::get_commit(&branch_name).ok();
+    let config = Git::get_branch_config(&branch_name).unwrap_or_default();
     let finish = Echo::progress(format!("delete branch {}", &branch_name));
     match Git::del_local_branch(&branch_name) {
         Err(err) => {
@@ -38,6 +110,8 @@ pub fn drop_task(branch_name: String, branch_type: BranchType) {
         }
         Ok(_) => finish(true, &format!("delete branch {}", &branch_name)),
     }
+    Journal::record_branch(&branch_name, before);
+    Journal::record_branch_config(config);
 
     // -- run after drop hook --
     let _ = run_hook(branch_type.after_drop.clone(), &branch_name, &branch_type);
@@ -1,14 +1,40 @@
+use std::{env, fs, path::PathBuf, process};
+
 use anyhow::{bail, Result};
 use regex::Regex;
+use tabled::{Table, Tabled};
 
 use crate::{
-    config::definition::{BranchType, Strategy, TargetBranch},
+    changelog::{generate_section, insert_section},
+    config::{
+        definition::{
+            BranchType, BumpStage, ChangelogConfig, ForgeConfig, Strategy, TagConfig, TargetBranch,
+            BRANCH_NAME_PLACEHOLDER, VERSION_PLACEHOLDER,
+        },
+        read::read_optional_config,
+    },
     echo::Echo,
+    forge::{find_open_pull_request, get_forge, render_pull_request, ForgeProvider, PullRequest},
     git::Git,
-    utils::run_hook,
+    journal::Journal,
+    lint::lint_message,
+    pattern::{replace_placeholders, whole_match_regex, Pattern},
+    ticket::{get_branch_ticket, Ticket},
+    utils::{bump_task_version, get_task_name, run_hook},
 };
 
-pub fn finish_task(branch_name: String, branch_type: BranchType) {
+const DEFAULT_REMOTE: &str = "origin";
+const DEFAULT_CHANGELOG_FILE: &str = "CHANGELOG.md";
+const DEFAULT_CHANGELOG_MESSAGE: &str = "docs: update changelog for {VERSION}";
+
+pub fn finish_task(
+    branch_name: String,
+    branch_type: BranchType,
+    worktree: bool,
+    forward_lines: Vec<String>,
+    check_pr: bool,
+    config_path: Option<PathBuf>,
+) {
     // -- validate branches --
     let branches = match Git::get_local_branches() {
         Err(err) => {
@@ -22,16 +48,30 @@ pub fn finish_task(branch_name: String, branch_type: BranchType) {
         return;
     }
 
-    // -- run before finish hook --
-    if run_hook(
-        branch_type.before_finish.clone(),
-        &branch_name,
-        &branch_type,
-    )
-    .is_err()
-    {
-        return;
-    }
+    // -- collect commits to forward port --
+    let forward_commits = match forward_lines.is_empty() {
+        true => Vec::new(),
+        false => match Git::diff_commits(&branch_name, &branch_type.from) {
+            Err(err) => {
+                Echo::error(err.to_string());
+                return;
+            }
+            Ok(commits) => commits.into_iter().rev().collect::<Vec<String>>(),
+        },
+    };
+
+    // -- get ticket --
+    let config = read_optional_config(config_path).ok().flatten();
+    let ticket = match &config {
+        None => None,
+        Some(config_v) => match get_branch_ticket(&branch_name, &branch_type, config_v) {
+            Err(err) => {
+                Echo::warning(format!("unable to get ticket: {}", err));
+                None
+            }
+            Ok(ticket_v) => ticket_v,
+        },
+    };
 
     // -- collect target branches --
     let mut target_branches = Vec::<TargetBranch>::new();
@@ -41,19 +81,155 @@ pub fn finish_task(branch_name: String, branch_type: BranchType) {
             if regex.is_match(x) {
                 target_branches.push(TargetBranch {
                     name: x.to_string(),
-                    strategy: y.strategy.clone(),
+                    ..y.clone()
                 });
                 break;
             }
         }
     });
+    let (pull_request_targets, target_branches): (Vec<TargetBranch>, Vec<TargetBranch>) =
+        target_branches
+            .into_iter()
+            .partition(|x| matches!(x.strategy, Strategy::PullRequest));
+    let forge_config = config.as_ref().and_then(|x| x.forge.clone());
 
-    // -- resolve target branches --
-    if resolve_target_branches(&branch_name, &target_branches).is_err() {
-        return;
+    // -- collect back merge targets --
+    let back_merge_targets = match &branch_type.back_merge {
+        None => Vec::new(),
+        Some(back_merge) => branches
+            .iter()
+            .filter(|x| **x != branch_name && **x != back_merge.from)
+            .filter(|x| {
+                back_merge
+                    .to
+                    .iter()
+                    .any(|y| whole_match_regex(y).is_ok_and(|z| z.is_match(x)))
+            })
+            .cloned()
+            .collect::<Vec<String>>(),
+    };
+
+    if check_pr {
+        // -- check pull requests --
+        if pull_request_targets.is_empty() {
+            Echo::error(format!(
+                "branch type {} has no pull-request targets",
+                &branch_type.name
+            ));
+            return;
+        }
+        match check_pull_requests(&branch_name, &pull_request_targets, forge_config) {
+            Err(err) => {
+                Echo::error(err.to_string());
+                return;
+            }
+            Ok(false) => return,
+            Ok(true) => (),
+        }
+    } else {
+        // -- lint commits --
+        if lint_commits(&branch_name, &branch_type).is_err() {
+            return;
+        }
+
+        // -- run before finish hook --
+        if run_hook(
+            branch_type.before_finish.clone(),
+            &branch_name,
+            &branch_type,
+        )
+        .is_err()
+        {
+            return;
+        }
+
+        // -- bump version --
+        if let Some(version_bump) = branch_type
+            .version_bump
+            .as_ref()
+            .filter(|x| x.on == BumpStage::Finish)
+        {
+            let result = match worktree {
+                true => in_worktree(&branch_name, || {
+                    bump_task_version(&branch_name, &branch_type, version_bump)
+                }),
+                false => bump_task_version(&branch_name, &branch_type, version_bump),
+            };
+            if result.is_err() {
+                return;
+            }
+        }
+
+        // -- update changelog --
+        if let Some(changelog) = &branch_type.changelog {
+            let result = match worktree {
+                true => in_worktree(&branch_name, || {
+                    update_changelog(&branch_name, &branch_type, changelog)
+                }),
+                false => update_changelog(&branch_name, &branch_type, changelog),
+            };
+            if result.is_err() {
+                return;
+            }
+        }
+
+        // -- resolve target branches --
+        if resolve_target_branches(
+            &branch_name,
+            &branch_type,
+            &target_branches,
+            &back_merge_targets,
+            worktree,
+            ticket.as_ref(),
+        )
+        .is_err()
+        {
+            return;
+        }
+
+        // -- forward port to newer lines --
+        for x in forward_lines.iter() {
+            let result = match worktree {
+                true => in_worktree(x, || forward_port(&forward_commits, x)),
+                false => forward_port(&forward_commits, x),
+            };
+            if result.is_err() {
+                return;
+            }
+        }
+
+        // -- open pull requests --
+        if !pull_request_targets.is_empty() {
+            if open_pull_requests(
+                &branch_name,
+                &branch_type,
+                &pull_request_targets,
+                forge_config,
+                ticket.as_ref(),
+            )
+            .is_err()
+            {
+                return;
+            }
+            Echo::info(format!(
+                "branch {} is kept until pull requests are merged, then run finish with --check-pr",
+                &branch_name
+            ));
+            return;
+        }
     }
 
     // -- delete branch --
+    if worktree && Git::get_current_branch().is_ok_and(|x| x == branch_name) {
+        Echo::warning(format!(
+            "branch {} is checked out, it will not be deleted",
+            &branch_name
+        ));
+        let _ = run_hook(branch_type.after_finish.clone(), &branch_name, &branch_type);
+        return;
+    }
+    let before = Git::get_commit(&branch_name).ok();
+    let config = Git::get_branch_config(&branch_name).unwrap_or_default();
     let finish = Echo::progress(format!("delete branch {}", &branch_name));
     match Git::del_local_branch(&branch_name) {
         Err(err) => {
@@ -62,29 +238,399 @@ pub fn finish_task(branch_name: String, branch_type: BranchType) {
         }
         Ok(_) => finish(true, &format!("delete branch {}", &branch_name)),
     }
+    Journal::record_branch(&branch_name, before);
+    Journal::record_branch_config(config);
 
     // -- run after finish hook --
     let _ = run_hook(branch_type.after_finish.clone(), &branch_name, &branch_type);
 }
 
-fn resolve_target_branches(branch_name: &str, target_branches: &Vec<TargetBranch>) -> Result<()> {
+/// Resolve the primary targets, then tag and merge the tagged branch back into back_merge_targets.
+fn resolve_target_branches(
+    branch_name: &str,
+    branch_type: &BranchType,
+    target_branches: &[TargetBranch],
+    back_merge_targets: &[String],
+    worktree: bool,
+    ticket: Option<&Ticket>,
+) -> Result<()> {
     for x in target_branches.iter() {
-        match x.strategy {
-            Strategy::Merge => {
-                merge(branch_name, &x.name)?;
+        match worktree {
+            true => in_worktree(&x.name, || resolve_target_branch(branch_name, x, ticket))?,
+            false => resolve_target_branch(branch_name, x, ticket)?,
+        }
+    }
+
+    // -- tag --
+    if let Some(tag) = &branch_type.tag {
+        tag_branch(branch_name, branch_type, tag)?;
+    }
+
+    // -- back merge --
+    if let Some(back_merge) = &branch_type.back_merge {
+        for x in back_merge_targets.iter() {
+            match worktree {
+                true => in_worktree(x, || back_merge_branch(&back_merge.from, x))?,
+                false => back_merge_branch(&back_merge.from, x)?,
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Tag the branch of tag, name and message are filled by placeholders of create.
+fn tag_branch(branch_name: &str, branch_type: &BranchType, tag: &TagConfig) -> Result<()> {
+    let values = Pattern::parse(&branch_type.create)
+        .and_then(|x| x.captures(&branch_type.placeholders, branch_name))
+        .ok()
+        .flatten()
+        .unwrap_or_default();
+    let name = replace_placeholders(
+        tag.name.as_deref().unwrap_or(BRANCH_NAME_PLACEHOLDER),
+        &values,
+    );
+    let message = tag
+        .message
+        .as_deref()
+        .map(|x| replace_placeholders(x, &values));
+
+    let msg = format!("tag {} as {}", &tag.branch, &name);
+    let finish = Echo::progress(&msg);
+    if let Err(err) = Git::create_tag(&name, &tag.branch, message.as_deref()) {
+        finish(false, &err.to_string());
+        bail!("");
+    }
+    finish(true, &msg);
+    Journal::record_ref(&format!("refs/tags/{}", &name), None);
+    Ok(())
+}
+
+fn back_merge_branch(source_branch: &str, target_branch: &str) -> Result<()> {
+    let before = Git::get_commit(target_branch).ok();
+    let result = merge(source_branch, target_branch, None);
+    Journal::record_branch(target_branch, before);
+    result
+}
+
+/// Run resolve in a temporary worktree of target_branch, so the current checkout stays untouched.
+fn in_worktree(target_branch: &str, resolve: impl FnOnce() -> Result<()>) -> Result<()> {
+    let current_branch = match Git::get_current_branch() {
+        Err(err) => {
+            Echo::error(err.to_string());
+            bail!("");
+        }
+        Ok(current_branch_v) => current_branch_v,
+    };
+
+    // -- a checked out branch cannot be added to another worktree, resolve it in place if clean --
+    if target_branch == current_branch {
+        let dirty_files = match Git::get_dirty_files() {
+            Err(err) => {
+                Echo::error(err.to_string());
+                bail!("");
+            }
+            Ok(dirty_files_v) => dirty_files_v,
+        };
+        if !dirty_files.is_empty() {
+            Echo::error(format!(
+                "branch {} is checked out and its working tree is not clean:\n{}",
+                target_branch,
+                dirty_files.join("\n")
+            ));
+            Echo::info("commit or stash these changes first, or switch to another branch");
+            bail!("");
+        }
+        return resolve();
+    }
+
+    // -- add worktree --
+    let worktree_path = env::temp_dir().join(format!(
+        "git-flow-{}-{}",
+        process::id(),
+        target_branch.replace('/', "-")
+    ));
+    let finish = Echo::progress(format!("add worktree for {}", target_branch));
+    if let Err(err) = Git::add_worktree(&worktree_path, target_branch) {
+        finish(false, &err.to_string());
+        bail!("");
+    }
+    finish(true, &format!("add worktree for {}", target_branch));
+
+    // -- resolve in worktree --
+    let current_dir = env::current_dir()?;
+    env::set_current_dir(&worktree_path)?;
+    let result = resolve();
+    env::set_current_dir(&current_dir)?;
+    if result.is_err() {
+        Echo::warning(format!(
+            "worktree {} is kept for manual resolution",
+            worktree_path.display()
+        ));
+        bail!("");
+    }
+
+    // -- remove worktree --
+    if let Err(err) = Git::remove_worktree(&worktree_path) {
+        Echo::warning(format!(
+            "unable to remove worktree {}: {}",
+            worktree_path.display(),
+            err
+        ));
+    }
+    Ok(())
+}
+
+fn forward_port(commits: &[String], target_branch: &str) -> Result<()> {
+    let before = Git::get_commit(target_branch).ok();
+    let result = cherry_pick_commits(commits.to_vec(), target_branch);
+    Journal::record_branch(target_branch, before);
+    result
+}
+
+fn resolve_target_branch(
+    branch_name: &str,
+    target_branch: &TargetBranch,
+    ticket: Option<&Ticket>,
+) -> Result<()> {
+    let before = Git::get_commit(&target_branch.name).ok();
+    let result = match target_branch.strategy {
+        Strategy::Merge => merge(branch_name, &target_branch.name, ticket),
+        Strategy::Rebase => rebase(branch_name, &target_branch.name),
+        Strategy::CherryPick => cherry_pick(branch_name, &target_branch.name),
+        Strategy::PullRequest => Ok(()),
+    };
+    Journal::record_branch(&target_branch.name, before);
+    result
+}
+
+/// Commit the changelog section of the task branch on it, the version is {NAME}.
+fn update_changelog(
+    branch_name: &str,
+    branch_type: &BranchType,
+    changelog: &ChangelogConfig,
+) -> Result<()> {
+    let finish = Echo::progress("update changelog");
+    let before = Git::get_commit(branch_name).ok();
+    let result = commit_changelog(branch_name, branch_type, changelog);
+    Journal::record_branch(branch_name, before);
+
+    match result {
+        Err(err) => {
+            finish(false, &err.to_string());
+            bail!("");
+        }
+        Ok(_) => {
+            finish(true, "update changelog");
+            Ok(())
+        }
+    }
+}
+
+fn commit_changelog(
+    branch_name: &str,
+    branch_type: &BranchType,
+    changelog: &ChangelogConfig,
+) -> Result<()> {
+    let version = get_task_name(branch_name, branch_type);
+    let section = generate_section(None, branch_name, &version)?;
+
+    // -- write on the task branch --
+    Git::switch(branch_name)?;
+    let file = changelog.file.as_deref().unwrap_or(DEFAULT_CHANGELOG_FILE);
+    let path = Git::get_root_dir()?.join(file);
+    let text = fs::read_to_string(&path).unwrap_or_default();
+    fs::write(&path, insert_section(&text, &section))?;
+
+    // -- commit --
+    let message = changelog
+        .message
+        .as_deref()
+        .unwrap_or(DEFAULT_CHANGELOG_MESSAGE)
+        .replace(VERSION_PLACEHOLDER, &version);
+    Git::commit_files(&[file], &message)
+}
+
+#[derive(Tabled)]
+struct LintFailure {
+    commit: String,
+    problems: String,
+}
+
+/// Check commits of the task branch by commit_lint of branch type.
+fn lint_commits(branch_name: &str, branch_type: &BranchType) -> Result<()> {
+    let rule = match &branch_type.commit_lint {
+        None => return Ok(()),
+        Some(rule_v) => rule_v,
+    };
+
+    let result = Git::diff_commits(branch_name, &branch_type.from).and_then(|commits| {
+        let mut failures = Vec::<LintFailure>::new();
+        for commit in commits.iter().rev() {
+            let message = Git::get_commit_message(commit)?;
+            let problems = lint_message(&message, rule, branch_type.ticket.as_ref())?;
+            if !problems.is_empty() {
+                failures.push(LintFailure {
+                    commit: Git::get_commit_summary(commit)?,
+                    problems: problems.join("\n"),
+                });
             }
-            Strategy::Rebase => {
-                rebase(branch_name, &x.name)?;
+        }
+        Ok(failures)
+    });
+
+    match result {
+        Err(err) => {
+            Echo::error(err.to_string());
+            bail!("");
+        }
+        Ok(failures) if failures.is_empty() => Ok(()),
+        Ok(failures) => {
+            Echo::error(format!(
+                "{} commits do not follow conventional commits",
+                failures.len()
+            ));
+            println!("{}", Table::new(failures));
+            bail!("");
+        }
+    }
+}
+
+/// Push the task branch, then open a pull request to each target if none is open.
+fn open_pull_requests(
+    branch_name: &str,
+    branch_type: &BranchType,
+    targets: &[TargetBranch],
+    forge_config: Option<ForgeConfig>,
+    ticket: Option<&Ticket>,
+) -> Result<()> {
+    let (forge, remote) = match get_forge_remote(forge_config) {
+        Err(err) => {
+            Echo::error(err.to_string());
+            bail!("");
+        }
+        Ok(forge_remote) => forge_remote,
+    };
+
+    // -- push branch --
+    let finish = Echo::progress(format!("push {} to {}", branch_name, &remote));
+    let before = Git::get_commit(&format!("refs/remotes/{}/{}", &remote, branch_name)).ok();
+    let refspec = format!("refs/heads/{}:refs/heads/{}", branch_name, branch_name);
+    let result = Git::push_refspecs(&remote, &[refspec], &[]).and_then(|statuses| {
+        match statuses.into_iter().find_map(|x| x.error) {
+            Some(error) => bail!(error),
+            None => Ok(()),
+        }
+    });
+    if let Err(err) = result {
+        finish(false, &err.to_string());
+        bail!("");
+    }
+    finish(true, &format!("push {} to {}", branch_name, &remote));
+    Journal::record_remote_branch(
+        &remote,
+        branch_name,
+        before,
+        Git::get_commit(branch_name).ok(),
+    );
+
+    // -- values of templates --
+    let mut values = Pattern::parse(&branch_type.create)
+        .and_then(|x| x.captures(&branch_type.placeholders, branch_name))
+        .ok()
+        .flatten()
+        .unwrap_or_default();
+    values.insert("BRANCH".to_string(), branch_name.to_string());
+    if let Some(ticket_v) = ticket {
+        values.insert("TICKET".to_string(), ticket_v.id.clone());
+        values.insert(
+            "TICKET_TITLE".to_string(),
+            ticket_v.title.clone().unwrap_or_default(),
+        );
+    }
+
+    // -- open pull requests --
+    for target in targets.iter() {
+        let msg = format!("open pull request from {} to {}", branch_name, &target.name);
+        let finish = Echo::progress(&msg);
+
+        let existing = match find_open_pull_request(forge.as_ref(), branch_name, &target.name) {
+            Err(err) => {
+                finish(false, &err.to_string());
+                bail!("");
             }
-            Strategy::CherryPick => {
-                cherry_pick(branch_name, &x.name)?;
+            Ok(existing_v) => existing_v,
+        };
+        if let Some(state) = existing {
+            finish(true, &format!("pull request {} is open", state.url));
+            continue;
+        }
+
+        values.insert("TARGET".to_string(), target.name.clone());
+        let (title, body) =
+            render_pull_request(target.title.as_deref(), target.body.as_deref(), &values);
+        let pull_request = PullRequest {
+            source_branch: branch_name.to_string(),
+            target_branch: target.name.clone(),
+            title,
+            body,
+        };
+        match forge.create_pull_request(&pull_request) {
+            Err(err) => {
+                finish(false, &err.to_string());
+                bail!("");
             }
+            Ok(state) => finish(true, &format!("open pull request {}", state.url)),
         }
     }
     Ok(())
 }
 
-fn merge(source_branch: &str, target_branch: &str) -> Result<()> {
+/// Whether pull requests to all targets are merged.
+fn check_pull_requests(
+    branch_name: &str,
+    targets: &[TargetBranch],
+    forge_config: Option<ForgeConfig>,
+) -> Result<bool> {
+    let (forge, _) = get_forge_remote(forge_config)?;
+
+    let mut merged = true;
+    for target in targets.iter() {
+        match forge.find_pull_request(branch_name, &target.name)? {
+            None => bail!(
+                "no pull request from {} to {}, run finish without --check-pr",
+                branch_name,
+                &target.name
+            ),
+            Some(state) if state.merged => {
+                Echo::success(format!("pull request {} is merged", state.url))
+            }
+            Some(state) if !state.open => bail!(
+                "pull request {} is closed without merge, run finish without --check-pr to open a new one",
+                state.url
+            ),
+            Some(state) => {
+                Echo::warning(format!("pull request {} is not merged", state.url));
+                merged = false;
+            }
+        }
+    }
+    Ok(merged)
+}
+
+fn get_forge_remote(forge_config: Option<ForgeConfig>) -> Result<(Box<dyn ForgeProvider>, String)> {
+    match forge_config {
+        None => bail!("forge is not configured"),
+        Some(forge_config_v) => Ok((
+            get_forge(&forge_config_v)?,
+            forge_config_v
+                .remote
+                .clone()
+                .unwrap_or(DEFAULT_REMOTE.to_string()),
+        )),
+    }
+}
+
+fn merge(source_branch: &str, target_branch: &str, ticket: Option<&Ticket>) -> Result<()> {
     let finish = Echo::progress(format!("merge {} into {}", source_branch, target_branch));
 
     // -- switch --
@@ -95,7 +641,13 @@ fn merge(source_branch: &str, target_branch: &str) -> Result<()> {
     }
 
     // -- merge --
-    let result = Git::merge(source_branch);
+    let result = match ticket {
+        None => Git::merge(source_branch),
+        Some(ticket_v) => Git::merge_with_message(
+            source_branch,
+            &ticket_v.merge_message(source_branch, target_branch),
+        ),
+    };
     if let Err(err) = result {
         finish(false, &err.to_string());
         bail!("");
@@ -141,7 +693,11 @@ fn cherry_pick(source_branch: &str, target_branch: &str) -> Result<()> {
         }
         Ok(commits_v) => commits_v,
     };
-    if commits.len() == 0 {
+    cherry_pick_commits(commits, target_branch)
+}
+
+fn cherry_pick_commits(commits: Vec<String>, target_branch: &str) -> Result<()> {
+    if commits.is_empty() {
         Echo::success(&format!("no commits to cherry pick to {}", target_branch));
         return Ok(());
     }
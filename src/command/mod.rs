@@ -1,7 +1,13 @@
+pub mod changelog;
 pub mod check;
+pub mod config;
 pub mod drop;
 pub mod finish;
 pub mod list;
+pub mod prune;
+pub mod restore;
 pub mod start;
+pub mod status;
 pub mod sync;
 pub mod track;
+pub mod undo;
@@ -0,0 +1,314 @@
+use std::{
+    path::PathBuf,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{bail, Result};
+use tabled::{Table, Tabled};
+
+use crate::{
+    config::{
+        definition::{BranchType, Config},
+        read::read_config,
+    },
+    echo::Echo,
+    git::Git,
+    journal::Journal,
+    pattern::branch_regex,
+    utils::{confirm, create_regex, push_refspecs, report_results, resolve_base, run_hook},
+};
+
+#[cfg(test)]
+mod test;
+
+#[derive(Tabled)]
+struct StaleBranch {
+    branch: String,
+    #[tabled(rename = "type")]
+    branch_type: String,
+    reason: String,
+    #[tabled(skip)]
+    merged: bool,
+    /// tip of the local branch
+    #[tabled(skip)]
+    commit: String,
+}
+
+pub fn prune_branches(
+    days: Option<u64>,
+    remote: Option<String>,
+    yes: bool,
+    run_hooks: bool,
+    config_path: Option<PathBuf>,
+) {
+    let config = match read_config(config_path) {
+        Err(err) => {
+            Echo::error(err.to_string());
+            return;
+        }
+        Ok(config_v) => config_v,
+    };
+
+    // -- find stale branches --
+    let branches = match Git::get_local_branches() {
+        Err(err) => {
+            Echo::error(err.to_string());
+            return;
+        }
+        Ok(branches_v) => branches_v,
+    };
+    let stale_branches = match find_stale_branches(&config, &branches, days) {
+        Err(err) => {
+            Echo::error(err.to_string());
+            return;
+        }
+        Ok(stale_branches_v) => stale_branches_v,
+    };
+    if stale_branches.is_empty() {
+        Echo::success("no stale branches");
+        return;
+    }
+
+    // -- confirm --
+    let repo = match select_repo(remote, &config) {
+        Err(err) => {
+            Echo::error(err.to_string());
+            return;
+        }
+        Ok(repo_v) => repo_v,
+    };
+    println!("{}", Table::new(&stale_branches));
+    let msg = match &repo {
+        None => "delete these branches?".to_string(),
+        Some(repo_v) => format!("delete these branches locally and on {}?", repo_v),
+    };
+    if !yes && !confirm(&msg) {
+        return;
+    }
+
+    // -- delete local branches --
+    let current_branch = Git::get_current_branch().unwrap_or_default();
+    let mut deleted_branches = Vec::<&StaleBranch>::new();
+    for stale_branch in stale_branches.iter() {
+        let branch = &stale_branch.branch;
+        if *branch == current_branch {
+            Echo::warning(format!("branch {} is checked out, skip it", branch));
+            continue;
+        }
+
+        let before = Git::get_commit(branch).ok();
+        let config = Git::get_branch_config(branch).unwrap_or_default();
+        let finish = Echo::progress(format!("delete branch {}", branch));
+        match Git::del_local_branch(branch) {
+            Err(err) => {
+                finish(false, &err.to_string());
+                continue;
+            }
+            Ok(_) => finish(true, &format!("delete branch {}", branch)),
+        }
+        Journal::record_branch(branch, before);
+        Journal::record_branch_config(config);
+        deleted_branches.push(stale_branch);
+    }
+
+    // -- delete remote branches --
+    if let Some(repo_v) = &repo {
+        del_remote_branches(repo_v, &deleted_branches, &config, &branches);
+    }
+
+    // -- run after finish hooks of merged branches --
+    if run_hooks {
+        for stale_branch in deleted_branches.iter().filter(|x| x.merged) {
+            let branch_type = config
+                .branch_types
+                .iter()
+                .find(|x| x.name == stale_branch.branch_type);
+            if let Some(branch_type_v) = branch_type {
+                let _ = run_hook(
+                    branch_type_v.after_finish.clone(),
+                    &stale_branch.branch,
+                    branch_type_v,
+                );
+            }
+        }
+    }
+}
+
+fn find_stale_branches(
+    config: &Config,
+    branches: &[String],
+    days: Option<u64>,
+) -> Result<Vec<StaleBranch>> {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+
+    let mut stale_branches = Vec::<StaleBranch>::new();
+    for branch_type in config.branch_types.iter() {
+        let regex = create_regex(branch_type)?;
+        for branch in branches.iter().filter(|x| regex.is_match(x)) {
+            if stale_branches.iter().any(|x| &x.branch == branch) {
+                continue;
+            }
+
+            let merged =
+                has_own_commits(branch, branch_type)? && is_merged(branch, branch_type, branches)?;
+            let idle_days = (now.saturating_sub(Git::get_commit_time(branch)?)) / (24 * 60 * 60);
+            let reason = if merged {
+                "merged into all targets".to_string()
+            } else if days.is_some_and(|x| idle_days >= x) {
+                format!("no commits for {} days", idle_days)
+            } else {
+                continue;
+            };
+
+            stale_branches.push(StaleBranch {
+                branch: branch.to_string(),
+                branch_type: branch_type.name.clone(),
+                reason,
+                merged,
+                commit: Git::get_commit(branch)?,
+            });
+        }
+    }
+    Ok(stale_branches)
+}
+
+/// Whether branch has commits not on from, or moved since it was created.
+/// A branch just started has none, so it is not taken as merged.
+fn has_own_commits(branch: &str, branch_type: &BranchType) -> Result<bool> {
+    // from with {BASE} is resolved by the recorded base, if any
+    if let Ok((resolved_v, _)) = resolve_base(branch, branch_type.clone(), None) {
+        if !Git::diff_commits(branch, &resolved_v.from)?.is_empty() {
+            return Ok(true);
+        }
+    }
+
+    // its commits may be merged into from already
+    let created = Git::get_created_commit(branch)?;
+    Ok(created.is_some_and(|x| x != Git::get_commit(branch).unwrap_or_default()))
+}
+
+/// merged if there are targets and none of them misses a commit of branch
+fn is_merged(branch: &str, branch_type: &BranchType, branches: &[String]) -> Result<bool> {
+    let mut targets = Vec::<&String>::new();
+    for x in branches.iter().filter(|x| x.as_str() != branch) {
+        for y in branch_type.to.iter() {
+            if branch_regex(&y.name, false)?.is_match(x) {
+                targets.push(x);
+                break;
+            }
+        }
+    }
+    if targets.is_empty() {
+        return Ok(false);
+    }
+
+    for target in targets {
+        if !Git::diff_commits(branch, target)?.is_empty() {
+            return Ok(false);
+        }
+    }
+    Ok(true)
+}
+
+/// --remote, then sync.push_to, then the only remote repo
+fn select_repo(remote: Option<String>, config: &Config) -> Result<Option<String>> {
+    let repos = Git::get_remote_repos()?;
+    match remote.or(config.sync.push_to.clone()) {
+        Some(repo) if repos.contains(&repo) => Ok(Some(repo)),
+        Some(repo) => bail!("remote repo {} is not found", repo),
+        None if repos.len() == 1 => Ok(Some(repos[0].clone())),
+        None => Ok(None),
+    }
+}
+
+/// Delete remote branches of deleted local branches in a single push.
+/// A remote branch is skipped if it has commits which are in neither the local branch nor its targets.
+fn del_remote_branches(
+    repo: &str,
+    stale_branches: &[&StaleBranch],
+    config: &Config,
+    branches: &[String],
+) {
+    let remote_branches = match Git::get_remote_branches(repo) {
+        Err(err) => {
+            Echo::error(err.to_string());
+            return;
+        }
+        Ok(remote_branches_v) => remote_branches_v,
+    };
+
+    // -- check remote branches --
+    let mut deleted_branches = Vec::<String>::new();
+    let mut before_list = Vec::<String>::new();
+    for stale_branch in stale_branches
+        .iter()
+        .filter(|x| remote_branches.contains(&x.branch))
+    {
+        let remote_ref = format!("refs/remotes/{}/{}", repo, &stale_branch.branch);
+        let before = match Git::get_commit(&remote_ref) {
+            Err(_) => continue,
+            Ok(before_v) => before_v,
+        };
+        match is_remote_merged(&remote_ref, stale_branch, config, branches) {
+            Err(err) => {
+                Echo::error(err.to_string());
+                continue;
+            }
+            Ok(false) => {
+                Echo::warning(format!(
+                    "{} has commits not merged, skip deleting it",
+                    &remote_ref
+                ));
+                continue;
+            }
+            Ok(true) => (),
+        }
+        deleted_branches.push(stale_branch.branch.to_string());
+        before_list.push(before);
+    }
+    if deleted_branches.is_empty() {
+        return;
+    }
+
+    // -- delete, unless remote branches moved since they were checked --
+    let refspecs = deleted_branches
+        .iter()
+        .map(|x| format!(":refs/heads/{}", x))
+        .collect::<Vec<String>>();
+    let leases = deleted_branches
+        .iter()
+        .zip(before_list.iter())
+        .map(|(x, y)| format!("refs/heads/{}:{}", x, y))
+        .collect::<Vec<String>>();
+
+    let step = format!("delete branches on {}", repo);
+    let finish = Echo::progress(&step);
+    let results = push_refspecs(repo, &deleted_branches, &refspecs, &leases);
+    for ((branch, result), before) in results.iter().zip(before_list) {
+        if result.is_ok() {
+            Journal::record_remote_branch(repo, branch, Some(before), None);
+        }
+    }
+    report_results(finish, &step, &results);
+}
+
+/// Whether the remote branch is contained by the deleted local branch, or is merged itself.
+fn is_remote_merged(
+    remote_ref: &str,
+    stale_branch: &StaleBranch,
+    config: &Config,
+    branches: &[String],
+) -> Result<bool> {
+    if Git::diff_commits(remote_ref, &stale_branch.commit)?.is_empty() {
+        return Ok(true);
+    }
+
+    let branch_type = config
+        .branch_types
+        .iter()
+        .find(|x| x.name == stale_branch.branch_type);
+    match branch_type {
+        None => Ok(false),
+        Some(branch_type_v) => is_merged(remote_ref, branch_type_v, branches),
+    }
+}
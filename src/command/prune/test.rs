@@ -0,0 +1,30 @@
+use std::process;
+
+use super::*;
+
+#[test]
+fn find_stale_branches_t() {
+    // a branch just started at HEAD, with HEAD as its from and target
+    let branch = format!("prune-test/{}", process::id());
+    Git::create_local_branch("HEAD", &branch).unwrap();
+
+    let config: Config = toml::from_str(
+        r#"
+[[branch_types]]
+name = "prune-test"
+create = "prune-test/{NAME}"
+from = "HEAD"
+to = [{ name = "HEAD", strategy = "merge" }]
+"#,
+    )
+    .unwrap();
+    let branches = vec![branch.clone(), "HEAD".to_string()];
+    let stale_branches = find_stale_branches(&config, &branches, None);
+    let idle_branches = find_stale_branches(&config, &branches, Some(0));
+    Git::del_local_branch(&branch).unwrap();
+
+    assert!(stale_branches.unwrap().is_empty());
+    let idle_branches = idle_branches.unwrap();
+    assert_eq!(idle_branches.len(), 1);
+    assert!(!idle_branches[0].merged);
+}
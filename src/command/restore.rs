@@ -0,0 +1,57 @@
+use crate::{
+    command::drop::{ARCHIVE_REF_PREFIX, ARCHIVE_TAG_PREFIX},
+    echo::Echo,
+    git::Git,
+    journal::Journal,
+};
+
+pub fn restore_task(branch_name: String) {
+    // -- validate branches --
+    let branches = match Git::get_local_branches() {
+        Err(err) => {
+            Echo::error(err.to_string());
+            return;
+        }
+        Ok(branches_v) => branches_v,
+    };
+    if branches.iter().any(|x| x.as_str() == branch_name) {
+        Echo::error(format!("branch {} does exist", branch_name));
+        return;
+    }
+
+    // -- find archive --
+    let archive = [ARCHIVE_REF_PREFIX, ARCHIVE_TAG_PREFIX]
+        .iter()
+        .map(|x| format!("{}{}", x, &branch_name))
+        .find_map(|x| Git::get_commit(&x).ok().map(|y| (x, y)));
+    let (ref_name, commit) = match archive {
+        None => {
+            Echo::error(format!("no archive of branch {} found", branch_name));
+            return;
+        }
+        Some(archive_v) => archive_v,
+    };
+
+    // -- create branch --
+    let finish = Echo::progress(format!(
+        "restore branch {} from {}",
+        &branch_name, &ref_name
+    ));
+    match Git::create_local_branch(&commit, &branch_name) {
+        Err(err) => {
+            finish(false, &err.to_string());
+            return;
+        }
+        Ok(_) => finish(
+            true,
+            &format!("restore branch {} from {}", &branch_name, &ref_name),
+        ),
+    }
+    Journal::record_branch(&branch_name, None);
+
+    // -- delete archive --
+    match Git::delete_ref(&ref_name) {
+        Err(err) => Echo::warning(format!("unable to delete {}: {}", &ref_name, err)),
+        Ok(_) => Journal::record_ref(&ref_name, Some(commit)),
+    }
+}
@@ -1,6 +1,33 @@
-use crate::{config::definition::BranchType, echo::Echo, git::Git, utils::run_hook};
+use anyhow::{bail, Result};
+
+use crate::{
+    config::definition::{BranchType, BumpStage, NAME_KEY},
+    echo::Echo,
+    git::Git,
+    journal::Journal,
+    pattern::{validate_name, Pattern},
+    ticket::{find_ticket, is_ticket, ticket_config_key},
+    utils::{base_config_key, bump_task_version, run_hook},
+};
+
+pub fn start_task(
+    branch_name: String,
+    branch_type: BranchType,
+    base: Option<String>,
+    ticket: Option<String>,
+) {
+    // -- validate name --
+    if let Err(err) = validate_branch_name(&branch_name, &branch_type) {
+        Echo::error(err.to_string());
+        return;
+    }
+
+    // -- validate ticket --
+    if let Err(err) = validate_ticket(&branch_name, &branch_type, ticket.as_deref()) {
+        Echo::error(err.to_string());
+        return;
+    }
 
-pub fn start_task(branch_name: String, branch_type: BranchType) {
     // -- validate branches --
     let branches = match Git::get_local_branches() {
         Err(err) => {
@@ -32,6 +59,27 @@ pub fn start_task(branch_name: String, branch_type: BranchType) {
         }
         Ok(_) => finish(true, &format!("create new branch {}", &branch_name)),
     }
+    Journal::record_branch(&branch_name, None);
+
+    // -- record base for finish --
+    if let Some(base_v) = base {
+        let key = base_config_key(&branch_name);
+        let before = Git::get_config(&key).ok().flatten();
+        match Git::set_config(&key, &base_v) {
+            Err(err) => Echo::warning(format!("unable to record base {}: {}", base_v, err)),
+            Ok(_) => Journal::record_config(&key, before),
+        }
+    }
+
+    // -- record ticket --
+    if let Some(ticket_v) = ticket {
+        let key = ticket_config_key(&branch_name);
+        let before = Git::get_config(&key).ok().flatten();
+        match Git::set_config(&key, &ticket_v) {
+            Err(err) => Echo::warning(format!("unable to record ticket {}: {}", ticket_v, err)),
+            Ok(_) => Journal::record_config(&key, before),
+        }
+    }
 
     // -- switch to new branch --
     let finish = Echo::progress(format!("switch to new branch {}", &branch_name));
@@ -43,6 +91,60 @@ pub fn start_task(branch_name: String, branch_type: BranchType) {
         Ok(_) => finish(true, &format!("switch to new branch {}", &branch_name)),
     }
 
+    // -- bump version --
+    if let Some(version_bump) = branch_type
+        .version_bump
+        .as_ref()
+        .filter(|x| x.on == BumpStage::Start)
+    {
+        if bump_task_version(&branch_name, &branch_type, version_bump).is_err() {
+            return;
+        }
+    }
+
     // -- run after start hook --
     let _ = run_hook(branch_type.after_start.clone(), &branch_name, &branch_type);
 }
+
+fn validate_branch_name(branch_name: &str, branch_type: &BranchType) -> Result<()> {
+    let values =
+        Pattern::parse(&branch_type.create)?.captures(&branch_type.placeholders, branch_name)?;
+    if let Some(name) = values.as_ref().and_then(|x| x.get(NAME_KEY)) {
+        validate_name(name, &branch_type.name_rule)?;
+    }
+    Git::check_branch_name(branch_name)
+}
+
+/// ticket is given by --ticket, otherwise it is found in branch_name
+fn validate_ticket(
+    branch_name: &str,
+    branch_type: &BranchType,
+    ticket: Option<&str>,
+) -> Result<()> {
+    let rule = match &branch_type.ticket {
+        None => {
+            if ticket.is_some() {
+                bail!("branch type {} links no ticket", &branch_type.name);
+            }
+            return Ok(());
+        }
+        Some(rule_v) => rule_v,
+    };
+
+    match ticket {
+        Some(ticket_v) => {
+            if !is_ticket(ticket_v, rule)? {
+                bail!("{} is not a ticket ID like {}", ticket_v, &rule.pattern);
+            }
+        }
+        None => {
+            if rule.required && find_ticket(branch_name, rule)?.is_none() {
+                bail!(
+                    "branch type {} requires a ticket ID in the name or by --ticket",
+                    &branch_type.name
+                );
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,98 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use tabled::{Table, Tabled};
+
+use crate::{
+    config::{definition::BranchType, read::read_config},
+    echo::Echo,
+    git::Git,
+    ticket::{get_provider, get_ticket},
+    utils::create_regex,
+};
+
+#[derive(Tabled)]
+struct TicketState {
+    ticket: String,
+    title: String,
+    branches: String,
+}
+
+pub fn show_status(config_path: Option<PathBuf>) {
+    let config = match read_config(config_path) {
+        Err(err) => {
+            Echo::error(err.to_string());
+            return;
+        }
+        Ok(config_v) => config_v,
+    };
+    let branches = match Git::get_local_branches() {
+        Err(err) => {
+            Echo::error(err.to_string());
+            return;
+        }
+        Ok(branches_v) => branches_v,
+    };
+
+    // -- group task branches by ticket --
+    let mut groups = Vec::<(Option<String>, Vec<String>)>::new();
+    for branch in branches.iter() {
+        let branch_type = config
+            .branch_types
+            .iter()
+            .find(|x| create_regex(x).is_ok_and(|y| y.is_match(branch)));
+        let ticket = match branch_type {
+            None => continue,
+            Some(branch_type_v) => match branch_ticket(branch, branch_type_v) {
+                Err(err) => {
+                    Echo::error(err.to_string());
+                    return;
+                }
+                Ok(ticket_v) => ticket_v,
+            },
+        };
+
+        match groups.iter_mut().find(|x| x.0 == ticket) {
+            Some(group) => group.1.push(branch.to_string()),
+            None => groups.push((ticket, vec![branch.to_string()])),
+        }
+    }
+    if groups.is_empty() {
+        Echo::info("no task branches");
+        return;
+    }
+    // branches without ticket come last
+    groups.sort_by(|a, b| (a.0.is_none(), &a.0).cmp(&(b.0.is_none(), &b.0)));
+
+    // -- resolve titles --
+    let provider = match get_provider(&config) {
+        Err(err) => {
+            Echo::warning(format!("unable to resolve ticket titles: {}", err));
+            None
+        }
+        Ok(provider_v) => provider_v,
+    };
+    let ticket_states = groups
+        .into_iter()
+        .map(|(ticket, branches)| TicketState {
+            title: match (&ticket, &provider) {
+                (Some(ticket_v), Some(provider_v)) => provider_v
+                    .title(ticket_v)
+                    .ok()
+                    .flatten()
+                    .unwrap_or_default(),
+                _ => String::new(),
+            },
+            ticket: ticket.unwrap_or("-".to_string()),
+            branches: branches.join("\n"),
+        })
+        .collect::<Vec<TicketState>>();
+    println!("{}", Table::new(ticket_states));
+}
+
+fn branch_ticket(branch: &str, branch_type: &BranchType) -> Result<Option<String>> {
+    match &branch_type.ticket {
+        None => Ok(None),
+        Some(rule) => get_ticket(branch, rule),
+    }
+}
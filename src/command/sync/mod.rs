@@ -0,0 +1,563 @@
+use std::{io, path::PathBuf};
+
+use anyhow::{bail, Context, Result};
+use regex::Regex;
+use tabled::{Table, Tabled};
+
+use crate::{
+    cli::{SyncAction, SyncStrategy, SyncTarget},
+    config::{
+        definition::{Config, SyncConfig},
+        read::read_optional_config,
+    },
+    echo::Echo,
+    git::Git,
+    journal::Journal,
+    pattern::{branch_regex, whole_match_regex},
+    utils::{confirm, create_regex, push_refspecs, report_results},
+};
+
+#[cfg(test)]
+mod test;
+
+pub fn sync_repo_branches(
+    action: SyncAction,
+    strategy: SyncStrategy,
+    yes: bool,
+    only: Option<String>,
+    types: Vec<String>,
+    remote: Option<String>,
+    config_path: Option<PathBuf>,
+) {
+    // -- read config --
+    let config = match read_optional_config(config_path) {
+        Err(err) => {
+            Echo::error(err.to_string());
+            return;
+        }
+        Ok(config_v) => config_v,
+    };
+    let sync_config = config.as_ref().map(|x| x.sync.clone()).unwrap_or_default();
+    let scope = match BranchScope::new(config.as_ref(), only.as_deref(), &types) {
+        Err(err) => {
+            Echo::error(err.to_string());
+            return;
+        }
+        Ok(scope_v) => scope_v,
+    };
+
+    // -- fetch remote data --
+    let finish = Echo::progress("fetch remote data");
+    let result = Git::fetch_remote_data();
+    match result {
+        Err(err) => {
+            finish(false, &err.to_string());
+            return;
+        }
+        Ok(_) => {
+            finish(true, "fetch remote data");
+        }
+    }
+
+    // -- select remote repo --
+    // pull from upstream, push to origin of a fork
+    let preferred_repo = match action {
+        SyncAction::Remote => sync_config.push_to.clone(),
+        SyncAction::Local | SyncAction::RepairTracking => sync_config.pull_from.clone(),
+    };
+    let repo = match select_repo(remote.or(preferred_repo)) {
+        Err(err) => {
+            Echo::error(err.to_string());
+            return;
+        }
+        Ok(value) => value,
+    };
+
+    // -- get branches --
+    let local_branches = match Git::get_local_branches() {
+        Err(err) => {
+            Echo::error(err.to_string());
+            return;
+        }
+        Ok(value) => scope.filter(value),
+    };
+    let remote_branches = match Git::get_remote_branches(&repo) {
+        Err(err) => {
+            Echo::error(err.to_string());
+            return;
+        }
+        Ok(value) => scope.filter(value),
+    };
+
+    // -- sync branches --
+    let target = match action {
+        SyncAction::Local => SyncTarget::Local,
+        SyncAction::Remote => SyncTarget::Remote,
+        SyncAction::RepairTracking => {
+            repair_tracking(&repo, &local_branches, &remote_branches);
+            return;
+        }
+    };
+    sync_branches(
+        &repo,
+        &target,
+        &strategy,
+        yes,
+        &sync_config,
+        &local_branches,
+        &remote_branches,
+    );
+}
+
+/// Set upstream of local branches without one to their remote counterparts.
+fn repair_tracking(repo: &str, local_branches: &[String], remote_branches: &[String]) {
+    let finish = Echo::progress("repair tracking");
+    let mut repaired_branches = Vec::<String>::new();
+    for branch in local_branches
+        .iter()
+        .filter(|x| remote_branches.contains(x))
+    {
+        let upstream = match Git::get_upstream(branch) {
+            Err(err) => {
+                finish(false, &err.to_string());
+                return;
+            }
+            Ok(upstream_v) => upstream_v,
+        };
+        if upstream.is_some() {
+            continue;
+        }
+
+        if let Err(err) = Git::set_upstream(branch, &format!("{}/{}", repo, branch)) {
+            finish(false, &err.to_string());
+            return;
+        }
+        repaired_branches.push(branch.to_string());
+    }
+
+    if repaired_branches.is_empty() {
+        finish(true, "all branches are tracked");
+    } else {
+        finish(
+            true,
+            &format!("\rrepair tracking: {}", repaired_branches.join(", ")),
+        );
+    }
+}
+
+/// Branches to sync, all branches if there is no config.
+struct BranchScope {
+    include: Option<Vec<Regex>>,
+    exclude: Vec<Regex>,
+    only: Option<Regex>,
+}
+
+impl BranchScope {
+    fn new(config: Option<&Config>, only: Option<&str>, types: &[String]) -> Result<BranchScope> {
+        let only = match only {
+            None => None,
+            Some(only_v) => Some(whole_match_regex(only_v).context("--only is not a valid regex")?),
+        };
+
+        let config = match config {
+            None if !types.is_empty() => bail!("--types requires a config file"),
+            None => {
+                return Ok(BranchScope {
+                    include: None,
+                    exclude: Vec::new(),
+                    only,
+                })
+            }
+            Some(config_v) => config_v,
+        };
+
+        let mut include = Vec::<Regex>::new();
+        if types.is_empty() {
+            // -- default: task, source and target branches of all branch types --
+            for branch_type in config.branch_types.iter() {
+                include.push(create_regex(branch_type)?);
+                include.push(branch_regex(&branch_type.from, true)?);
+                for target in branch_type.to.iter() {
+                    include.push(branch_regex(&target.name, false)?);
+                }
+            }
+            for x in config.sync.include.iter() {
+                include.push(whole_match_regex(x)?);
+            }
+        } else {
+            for x in types.iter() {
+                match config.branch_types.iter().find(|y| &y.name == x) {
+                    None => bail!("branch type {} is not found", x),
+                    Some(branch_type) => include.push(create_regex(branch_type)?),
+                }
+            }
+        }
+
+        let exclude = config
+            .sync
+            .exclude
+            .iter()
+            .map(|x| whole_match_regex(x))
+            .collect::<Result<Vec<Regex>>>()?;
+
+        Ok(BranchScope {
+            include: Some(include),
+            exclude,
+            only,
+        })
+    }
+
+    fn contains(&self, branch: &str) -> bool {
+        let included = match &self.include {
+            None => true,
+            Some(include) => include.iter().any(|x| x.is_match(branch)),
+        };
+        let excluded = self.exclude.iter().any(|x| x.is_match(branch));
+        let only = match &self.only {
+            None => true,
+            Some(only) => only.is_match(branch),
+        };
+        included && !excluded && only
+    }
+
+    fn filter(&self, branches: Vec<String>) -> Vec<String> {
+        branches.into_iter().filter(|x| self.contains(x)).collect()
+    }
+}
+
+fn select_repo(name: Option<String>) -> Result<String> {
+    // -- fetch repos --
+    let repos = Git::get_remote_repos()?;
+    if repos.is_empty() {
+        bail!("no remote repo specified");
+    }
+
+    // -- select repo by name --
+    if let Some(name_v) = name {
+        if !repos.contains(&name_v) {
+            bail!("remote repo {} is not found", name_v);
+        }
+        return Ok(name_v);
+    }
+
+    // -- select the only repo --
+    if repos.len() == 1 {
+        return Ok(repos[0].clone());
+    }
+
+    // -- user select target repo --
+    println!("select target repo:");
+    repos
+        .iter()
+        .enumerate()
+        .for_each(|(i, x)| println!("[{}] {}", i + 1, x));
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+
+    let index = input.trim().parse::<usize>().context("invalid input")?;
+    if index == 0 || index > repos.len() {
+        bail!("invalid input");
+    }
+    Ok(repos[index - 1].clone())
+}
+
+fn sync_branches(
+    repo: &str,
+    target: &SyncTarget,
+    strategy: &SyncStrategy,
+    yes: bool,
+    sync_config: &SyncConfig,
+    local_branches: &Vec<String>,
+    remote_branches: &Vec<String>,
+) {
+    let source_branches = match target {
+        SyncTarget::Local => remote_branches,
+        SyncTarget::Remote => local_branches,
+    };
+
+    let target_branches = match target {
+        SyncTarget::Local => local_branches,
+        SyncTarget::Remote => remote_branches,
+    };
+
+    // -- remove redundant branches --
+    if let SyncStrategy::Override = strategy {
+        let (protected_branches, redundant_branches): (Vec<String>, Vec<String>) = target_branches
+            .iter()
+            .filter(|x| !source_branches.iter().any(|y| x.as_str() == y))
+            .map(|x| x.to_string())
+            .partition(|x| is_protected(x, &sync_config.protected));
+        if !protected_branches.is_empty() {
+            Echo::info(format!(
+                "skip protected branches: {}",
+                protected_branches.join(", ")
+            ));
+        }
+        if redundant_branches.is_empty() {
+            Echo::success("no redundant branches");
+        } else if !confirm_redundant_branches(target, repo, &redundant_branches, yes) {
+            Echo::warning("skip removing redundant branches");
+        } else {
+            let finish = Echo::progress("remove redundant branches");
+            let results = del_branches(target, repo, &redundant_branches);
+            report_results(finish, "remove redundant branches", &results);
+        }
+    }
+
+    // -- create missing branches --
+    let missing_branches = source_branches
+        .iter()
+        .filter(|x| !target_branches.iter().any(|y| x.as_str() == y))
+        .map(|x| x.to_string())
+        .collect::<Vec<String>>();
+    if missing_branches.is_empty() {
+        Echo::success("no missing branches");
+    } else {
+        let finish = Echo::progress("create missing branches");
+        let results = create_branches(target, repo, &missing_branches);
+        report_results(finish, "create missing branches", &results);
+    }
+
+    // -- update existing branches --
+    update_branches(target, repo, local_branches, remote_branches);
+}
+
+/// Fast-forward or push branches existing on both sides, report the others.
+fn update_branches(
+    target: &SyncTarget,
+    repo: &str,
+    local_branches: &[String],
+    remote_branches: &[String],
+) {
+    let current_branch = Git::get_current_branch().unwrap_or_default();
+
+    let finish = Echo::progress("update existing branches");
+    let mut branch_states = Vec::<BranchState>::new();
+    let mut pending_pushes = Vec::<(usize, String)>::new();
+    for branch in local_branches
+        .iter()
+        .filter(|x| remote_branches.contains(x))
+    {
+        let remote_ref = format!("refs/remotes/{}/{}", repo, branch);
+        let (ahead, behind) =
+            match Git::count_ahead_behind(&format!("refs/heads/{}", branch), &remote_ref) {
+                Err(err) => {
+                    branch_states.push(BranchState::new(branch, 0, 0, error_status(err)));
+                    continue;
+                }
+                Ok(counts) => counts,
+            };
+        if ahead == 0 && behind == 0 {
+            continue;
+        }
+
+        let status = if ahead > 0 && behind > 0 {
+            "diverged, skipped".to_string()
+        } else if behind > 0 {
+            match target {
+                SyncTarget::Local => {
+                    match fast_forward_branch(branch, &remote_ref, &current_branch) {
+                        Err(err) => error_status(err),
+                        Ok(_) => "fast-forwarded".to_string(),
+                    }
+                }
+                SyncTarget::Remote => "behind, skipped".to_string(),
+            }
+        } else {
+            match target {
+                SyncTarget::Local => "ahead, skipped".to_string(),
+                SyncTarget::Remote => {
+                    pending_pushes.push((branch_states.len(), branch.to_string()));
+                    "pending".to_string()
+                }
+            }
+        };
+        branch_states.push(BranchState::new(branch, ahead, behind, status));
+    }
+
+    // -- push ahead branches at once --
+    let branches = pending_pushes
+        .iter()
+        .map(|(_, x)| x.to_string())
+        .collect::<Vec<String>>();
+    let results = push_branches(repo, &branches);
+    for ((index, _), (_, result)) in pending_pushes.iter().zip(results) {
+        branch_states[*index].status = match result {
+            Err(err) => error_status(err),
+            Ok(_) => "pushed".to_string(),
+        };
+    }
+    finish(true, "update existing branches");
+
+    if branch_states.is_empty() {
+        Echo::success("all existing branches are up to date");
+    } else {
+        println!("{}", Table::new(branch_states));
+    }
+}
+
+#[derive(Tabled)]
+struct BranchState {
+    branch: String,
+    ahead: usize,
+    behind: usize,
+    status: String,
+}
+
+impl BranchState {
+    fn new(branch: &str, ahead: usize, behind: usize, status: String) -> BranchState {
+        BranchState {
+            branch: branch.to_string(),
+            ahead,
+            behind,
+            status,
+        }
+    }
+}
+
+fn error_status(err: anyhow::Error) -> String {
+    format!(
+        "failed: {}",
+        err.to_string().lines().next().unwrap_or_default()
+    )
+}
+
+fn fast_forward_branch(branch: &str, remote_ref: &str, current_branch: &str) -> Result<()> {
+    let before = Git::get_commit(branch).ok();
+    if branch == current_branch {
+        Git::merge_ff_only(remote_ref)?;
+    } else {
+        Git::update_ref(
+            &format!("refs/heads/{}", branch),
+            &Git::get_commit(remote_ref)?,
+        )?;
+    }
+    Journal::record_branch(branch, before);
+    Ok(())
+}
+
+/// Push local branches to remote repo in a single push.
+fn push_branches(repo: &str, branches: &[String]) -> Vec<(String, Result<()>)> {
+    let before_list = branches
+        .iter()
+        .map(|x| Git::get_commit(&format!("refs/remotes/{}/{}", repo, x)).ok())
+        .collect::<Vec<Option<String>>>();
+    let refspecs = branches
+        .iter()
+        .map(|x| format!("refs/heads/{}:refs/heads/{}", x, x))
+        .collect::<Vec<String>>();
+
+    let results = push_refspecs(repo, branches, &refspecs, &[]);
+    for ((branch, result), before) in results.iter().zip(before_list) {
+        if result.is_ok() {
+            Journal::record_remote_branch(repo, branch, before, Git::get_commit(branch).ok());
+        }
+    }
+    results
+}
+
+fn is_protected(branch: &str, protected: &[String]) -> bool {
+    protected
+        .iter()
+        .any(|x| whole_match_regex(x).map_or(true, |y| y.is_match(branch)))
+}
+
+/// Preview redundant branches with the commits that would be lost, and ask for confirmation.
+fn confirm_redundant_branches(
+    target: &SyncTarget,
+    repo: &str,
+    redundant_branches: &[String],
+    yes: bool,
+) -> bool {
+    match target {
+        SyncTarget::Local => Echo::warning("these local branches will be deleted:"),
+        SyncTarget::Remote => Echo::warning(format!("these branches on {} will be deleted:", repo)),
+    }
+    for branch in redundant_branches.iter() {
+        let lost_commits = match target {
+            SyncTarget::Local => Git::count_unique_commits(
+                &format!("refs/heads/{}", branch),
+                &format!("--remotes={}", repo),
+            ),
+            SyncTarget::Remote => Git::count_unique_commits(
+                &format!("refs/remotes/{}/{}", repo, branch),
+                "--branches",
+            ),
+        };
+        match lost_commits {
+            Ok(0) => println!("  {}", branch),
+            Ok(count) => println!("  {} ({} commits will be lost)", branch, count),
+            Err(_) => println!("  {} (unable to count commits that will be lost)", branch),
+        }
+    }
+
+    yes || confirm("delete these branches?")
+}
+
+/// Delete branches, remote branches are deleted in a single push.
+fn del_branches(target: &SyncTarget, repo: &str, branches: &[String]) -> Vec<(String, Result<()>)> {
+    match target {
+        SyncTarget::Remote => {
+            let before_list = branches
+                .iter()
+                .map(|x| Git::get_commit(&format!("refs/remotes/{}/{}", repo, x)).ok())
+                .collect::<Vec<Option<String>>>();
+            let refspecs = branches
+                .iter()
+                .map(|x| format!(":refs/heads/{}", x))
+                .collect::<Vec<String>>();
+
+            let results = push_refspecs(repo, branches, &refspecs, &[]);
+            for ((branch, result), before) in results.iter().zip(before_list) {
+                if result.is_ok() {
+                    Journal::record_remote_branch(repo, branch, before, None);
+                }
+            }
+            results
+        }
+        SyncTarget::Local => branches
+            .iter()
+            .map(|branch| {
+                let before = Git::get_commit(branch).ok();
+                let config = Git::get_branch_config(branch).unwrap_or_default();
+                let result = Git::del_local_branch(branch);
+                if result.is_ok() {
+                    Journal::record_branch(branch, before);
+                    Journal::record_branch_config(config);
+                }
+                (branch.to_string(), result)
+            })
+            .collect(),
+    }
+}
+
+/// Create branches tracking each other, remote branches are created in a single push.
+fn create_branches(
+    target: &SyncTarget,
+    repo: &str,
+    branches: &[String],
+) -> Vec<(String, Result<()>)> {
+    let results = match target {
+        SyncTarget::Remote => push_branches(repo, branches),
+        SyncTarget::Local => branches
+            .iter()
+            .map(|branch| {
+                let result = Git::create_local_branch(&format!("{}/{}", repo, branch), branch);
+                if result.is_ok() {
+                    Journal::record_branch(branch, None);
+                }
+                (branch.to_string(), result)
+            })
+            .collect(),
+    };
+
+    results
+        .into_iter()
+        .map(|(branch, result)| {
+            let result =
+                result.and_then(|_| Git::set_upstream(&branch, &format!("{}/{}", repo, branch)));
+            (branch, result)
+        })
+        .collect()
+}
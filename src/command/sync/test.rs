@@ -0,0 +1,20 @@
+use super::*;
+
+#[test]
+fn branch_scope_t() {
+    let config: Config = toml::from_str(
+        r#"
+[[branch_types]]
+name = "support-fix"
+create = "support-fix/{NAME}"
+from = "support/{BASE}"
+to = [{ name = "support/{BASE}", strategy = "merge" }]
+"#,
+    )
+    .unwrap();
+
+    let scope = BranchScope::new(Some(&config), None, &[]).unwrap();
+    assert!(scope.contains("support/1.x"));
+    assert!(scope.contains("support-fix/a"));
+    assert!(!scope.contains("main"));
+}
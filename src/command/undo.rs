@@ -0,0 +1,145 @@
+use std::collections::HashMap;
+
+use anyhow::{bail, Result};
+
+use crate::{
+    echo::Echo,
+    git::Git,
+    journal::{group_operations, Entry, Journal},
+};
+
+pub fn undo_operations(steps: usize) {
+    // -- read journal --
+    let entries = match Journal::read() {
+        Err(err) => {
+            Echo::error(err.to_string());
+            return;
+        }
+        Ok(entries_v) => entries_v,
+    };
+    let operations = group_operations(&entries);
+    if operations.is_empty() {
+        Echo::warning("nothing to undo");
+        return;
+    }
+
+    // -- undo from the latest --
+    for operation in operations.iter().rev().take(steps) {
+        if undo_operation(operation).is_err() {
+            return;
+        }
+
+        if let Err(err) = Journal::remove_operations(&[operation[0].operation.clone()]) {
+            Echo::error(err.to_string());
+            return;
+        }
+    }
+}
+
+fn undo_operation(entries: &[Entry]) -> Result<()> {
+    let command = &entries[0].command;
+
+    // -- check refs are not changed since --
+    if let Err(err) = check_entries(entries) {
+        Echo::error(format!("unable to undo {}: {}", command, err));
+        bail!("");
+    }
+
+    // -- restore refs --
+    let finish = Echo::progress(format!("undo {}", command));
+    for entry in entries.iter().rev() {
+        if let Err(err) = undo_entry(entry) {
+            finish(false, &format!("{}: {}", display_ref(entry), err));
+            bail!("");
+        }
+    }
+    finish(true, &format!("undo {}", command));
+    Ok(())
+}
+
+fn check_entries(entries: &[Entry]) -> Result<()> {
+    let current_branch = Git::get_current_branch()?;
+    let mut refs = HashMap::<String, Option<String>>::new();
+
+    for entry in entries.iter().rev() {
+        let key = display_ref(entry);
+        let current = refs
+            .entry(key.clone())
+            .or_insert_with(|| current_commit(entry));
+        if *current != entry.after {
+            bail!("{} has changed since", key);
+        }
+        if entry.remote.is_none()
+            && entry.before.is_none()
+            && entry.ref_name == format!("refs/heads/{}", current_branch)
+        {
+            bail!(
+                "branch {} is checked out, switch to another branch first",
+                current_branch
+            );
+        }
+        *current = entry.before.clone();
+    }
+
+    Ok(())
+}
+
+fn undo_entry(entry: &Entry) -> Result<()> {
+    if let Some(key) = entry.config_key() {
+        return match &entry.before {
+            None => Git::unset_config(key),
+            Some(before) => Git::set_config(key, before),
+        };
+    }
+    if let Some(repo) = &entry.remote {
+        return undo_remote_entry(repo, entry);
+    }
+
+    let current_branch = Git::get_current_branch()?;
+    match &entry.before {
+        None => Git::delete_ref(&entry.ref_name),
+        Some(before) if entry.ref_name == format!("refs/heads/{}", current_branch) => {
+            Git::reset_keep(before)
+        }
+        Some(before) => Git::update_ref(&entry.ref_name, before),
+    }
+}
+
+/// Reset or delete the remote branch, rejected if it is no longer at after on the remote repo,
+/// since the remote-tracking ref checked by check_entries may be stale.
+fn undo_remote_entry(repo: &str, entry: &Entry) -> Result<()> {
+    let refspec = match &entry.before {
+        None => format!(":refs/heads/{}", &entry.ref_name),
+        Some(before) => format!("{}:refs/heads/{}", before, &entry.ref_name),
+    };
+    // an empty lease value expects the branch not to exist
+    let lease = format!(
+        "refs/heads/{}:{}",
+        &entry.ref_name,
+        entry.after.as_deref().unwrap_or_default()
+    );
+
+    let statuses = Git::push_refspecs(repo, &[refspec], &[lease])?;
+    match statuses.into_iter().find_map(|x| x.error) {
+        Some(error) => bail!(error),
+        None => Ok(()),
+    }
+}
+
+/// Current commit of the ref, or the current value of the config key.
+fn current_commit(entry: &Entry) -> Option<String> {
+    if let Some(key) = entry.config_key() {
+        return Git::get_config(key).ok().flatten();
+    }
+    match &entry.remote {
+        None => Git::get_commit(&entry.ref_name).ok(),
+        Some(repo) => Git::get_commit(&format!("refs/remotes/{}/{}", repo, entry.ref_name)).ok(),
+    }
+}
+
+fn display_ref(entry: &Entry) -> String {
+    match &entry.remote {
+        None => entry.config_key().unwrap_or(&entry.ref_name).to_string(),
+        Some(repo) => format!("{}/{}", repo, entry.ref_name),
+    }
+}
@@ -1,11 +1,71 @@
-use serde::Deserialize;
+use std::collections::BTreeMap;
 
-#[derive(Debug, Deserialize, Clone)]
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct Config {
     pub branch_types: Vec<BranchType>,
+    #[serde(default)]
+    pub sync: SyncConfig,
+    #[serde(default)]
+    pub tickets: TicketsConfig,
+    pub forge: Option<ForgeConfig>,
+}
+
+/// Forge to open pull requests on
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ForgeConfig {
+    pub kind: ForgeKind,
+    /// owner/name of the repo on forge
+    pub repo: String,
+    /// api root, defaults to github.com or gitlab.com, required by gitea
+    pub api_url: Option<String>,
+    /// env of the api token, defaults to GIT_FLOW_TOKEN
+    pub token_env: Option<String>,
+    /// remote repo task branches are pushed to, defaults to origin
+    pub remote: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub enum ForgeKind {
+    #[serde(rename = "github")]
+    GitHub,
+    #[serde(rename = "gitlab")]
+    GitLab,
+    #[serde(rename = "gitea")]
+    Gitea,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct TicketsConfig {
+    /// json or toml file mapping ticket IDs to titles, relative to the git root
+    pub file: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct SyncConfig {
+    /// regexes of branches which are never deleted by sync
+    #[serde(default)]
+    pub protected: Vec<String>,
+    /// regexes of extra branches to sync
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// regexes of branches not to sync
+    #[serde(default)]
+    pub exclude: Vec<String>,
+    /// remote repo to sync local branches from, e.g. the canonical repo of a fork
+    pub pull_from: Option<String>,
+    /// remote repo to sync local branches to, e.g. the fork
+    pub push_to: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct BranchType {
     pub name: String,
     pub create: String,
@@ -17,15 +77,143 @@ pub struct BranchType {
     pub after_finish: Option<Command>,
     pub before_drop: Option<Command>,
     pub after_drop: Option<Command>,
+    /// regexes values of placeholders in create should match, e.g. TICKET = "[A-Z]+-\\d+"
+    #[serde(default)]
+    pub placeholders: BTreeMap<String, String>,
+    /// cherry pick commits to newer lines matching from, if from includes {BASE}
+    #[serde(default)]
+    pub forward_port: bool,
+    #[serde(default)]
+    pub name_rule: NameRule,
+    pub ticket: Option<TicketRule>,
+    pub commit_lint: Option<CommitLint>,
+    pub changelog: Option<ChangelogConfig>,
+    pub version_bump: Option<VersionBump>,
+    pub tag: Option<TagConfig>,
+    pub back_merge: Option<BackMerge>,
+}
+
+/// Tag created on a target branch after the targets are resolved
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct TagConfig {
+    /// target branch to tag, e.g. "main"
+    pub branch: String,
+    /// tag name template, defaults to "{NAME}"
+    pub name: Option<String>,
+    /// message template of an annotated tag, the tag is lightweight if unset
+    pub message: Option<String>,
+}
+
+/// Merge of the updated production branch back into other branches, after tagging
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct BackMerge {
+    /// branch to merge from, e.g. "main"
+    pub from: String,
+    /// regexes of branches to merge into, matching whole branch names, e.g. "dev" and "release/.*"
+    pub to: Vec<String>,
+}
+
+/// Version files updated on the task branch, {NAME} is the version
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct VersionBump {
+    #[serde(default)]
+    pub on: BumpStage,
+    pub files: Vec<VersionFile>,
+    /// commit message template, defaults to "chore: bump version to {VERSION}"
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Default, PartialEq)]
+pub enum BumpStage {
+    #[default]
+    #[serde(rename = "start")]
+    Start,
+    #[serde(rename = "finish")]
+    Finish,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct VersionFile {
+    /// path relative to the git root
+    pub path: String,
+    /// regex whose first group is the version, required by files other than Cargo.toml, package.json and pyproject.toml
+    pub pattern: Option<String>,
+}
+
+/// Changelog updated on the task branch before finish, {NAME} is the version
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct ChangelogConfig {
+    /// path relative to the git root, defaults to CHANGELOG.md
+    pub file: Option<String>,
+    /// commit message template, defaults to "docs: update changelog for {VERSION}"
+    pub message: Option<String>,
+}
+
+/// Conventional Commits rules of task commits, checked on finish
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct CommitLint {
+    /// allowed types, any type if empty
+    #[serde(default)]
+    pub types: Vec<String>,
+    /// allowed scopes, any scope if empty
+    #[serde(default)]
+    pub scopes: Vec<String>,
+    pub max_header_length: Option<usize>,
+    /// whether commits should reference a ticket ID of ticket.pattern
+    #[serde(default)]
+    pub require_ticket: bool,
+}
+
+/// Ticket ID linked to task branches
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct TicketRule {
+    /// regex of ticket IDs, e.g. "[A-Z]+-\\d+"
+    pub pattern: String,
+    /// whether start fails without ticket ID
+    #[serde(default)]
+    pub required: bool,
+}
+
+/// Rules of {NAME} on start
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone, Default)]
+#[serde(deny_unknown_fields)]
+pub struct NameRule {
+    /// regex the whole name should match
+    pub allowed: Option<String>,
+    /// max number of characters
+    pub max_length: Option<usize>,
+    pub normalize: Option<Normalize>,
+    /// regex the name should start with, e.g. "[A-Z]+-\\d+-", kept as is by normalize
+    pub ticket_prefix: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+pub enum Normalize {
+    #[serde(rename = "lowercase")]
+    Lowercase,
+    #[serde(rename = "kebab-case")]
+    KebabCase,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct TargetBranch {
     pub name: String,
     pub strategy: Strategy,
+    /// title template of pull-request, e.g. "Merge {BRANCH} into {TARGET}"
+    pub title: Option<String>,
+    /// body template of pull-request
+    pub body: Option<String>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
 pub enum Strategy {
     #[serde(rename = "merge")]
     Merge,
@@ -33,12 +221,19 @@ pub enum Strategy {
     Rebase,
     #[serde(rename = "cherry-pick")]
     CherryPick,
+    /// open a pull request on forge instead of resolving locally
+    #[serde(rename = "pull-request")]
+    PullRequest,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, JsonSchema, Clone)]
+#[serde(deny_unknown_fields)]
 pub struct Command {
     pub command: String,
     pub args: Vec<String>,
 }
 
 pub const BRANCH_NAME_PLACEHOLDER: &str = "{NAME}";
+pub const NAME_KEY: &str = "NAME";
+pub const VERSION_PLACEHOLDER: &str = "{VERSION}";
+pub const BASE_PLACEHOLDER: &str = "{BASE}";
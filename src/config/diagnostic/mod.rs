@@ -0,0 +1,232 @@
+use std::{fmt, ops::Range, path::Path};
+
+use toml_edit::{ImDocument, Item};
+
+use super::{definition::Config, validate::validate_config};
+
+#[cfg(test)]
+mod test;
+
+/// A config error, located by the keys of the offending value or by a span of the text.
+#[derive(Debug, Clone, Default)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    pub keys: Vec<Key>,
+    pub span: Option<Range<usize>>,
+    pub hint: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum Severity {
+    #[default]
+    Error,
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Key {
+    Field(String),
+    Index(usize),
+}
+
+impl Diagnostic {
+    pub fn new<T: Into<String>>(message: T) -> Diagnostic {
+        Diagnostic {
+            message: message.into(),
+            ..Default::default()
+        }
+    }
+
+    pub fn warning(mut self) -> Diagnostic {
+        self.severity = Severity::Warning;
+        self
+    }
+
+    pub fn key(mut self, key: &str) -> Diagnostic {
+        self.keys.push(Key::Field(key.to_string()));
+        self
+    }
+
+    pub fn index(mut self, index: usize) -> Diagnostic {
+        self.keys.push(Key::Index(index));
+        self
+    }
+
+    /// Shorthand of key("branch_types").index(index).
+    pub fn branch_type(self, index: usize) -> Diagnostic {
+        self.key("branch_types").index(index)
+    }
+
+    pub fn hint<T: Into<String>>(mut self, hint: T) -> Diagnostic {
+        self.hint = Some(hint.into());
+        self
+    }
+
+    /// Diagnostic of a parse error, unknown fields are hinted with the closest expected field.
+    pub fn from_toml(err: &toml::de::Error) -> Diagnostic {
+        let message = err.message().trim().to_string();
+        let mut diagnostic = match message.strip_prefix("unknown field ") {
+            None => match message.split_once('\n') {
+                None => Diagnostic::new(message.as_str()),
+                Some((first, rest)) => Diagnostic::new(first).hint(rest.replace('\n', " ")),
+            },
+            Some(rest) => {
+                let names = rest.split('`').skip(1).step_by(2).collect::<Vec<&str>>();
+                let mut diagnostic = Diagnostic::new(format!("unknown field `{}`", names[0]));
+                if let Some(closest) = closest_name(names[0], &names[1..]) {
+                    diagnostic = diagnostic.hint(format!("did you mean `{}`?", closest));
+                } else if names.len() > 1 {
+                    diagnostic =
+                        diagnostic.hint(format!("expected one of `{}`", names[1..].join("`, `")));
+                }
+                diagnostic
+            }
+        };
+        diagnostic.span = err.span();
+        diagnostic
+    }
+
+    /// Render like rustc, with the path, line, column and the offending line of text.
+    pub fn render(&self, path: &Path, text: &str) -> String {
+        let span = self.span.clone().or_else(|| locate(text, &self.keys));
+        let span = match span {
+            None => {
+                let mut lines = vec![
+                    format!("{}: {}", self.label(), self.message),
+                    format!(" --> {}", path.display()),
+                ];
+                if let Some(hint) = &self.hint {
+                    lines.push(format!("  = hint: {}", hint));
+                }
+                return lines.join("\n");
+            }
+            Some(span_v) => span_v,
+        };
+
+        // -- position --
+        let line_start = text[..span.start].rfind('\n').map_or(0, |x| x + 1);
+        let line_end = text[span.start..]
+            .find('\n')
+            .map_or(text.len(), |x| span.start + x);
+        let line_number = text[..span.start].matches('\n').count() + 1;
+        let column = text[line_start..span.start].chars().count() + 1;
+        let width = text[span.start..span.end.min(line_end)]
+            .trim_end()
+            .chars()
+            .count()
+            .max(1);
+
+        // -- snippet --
+        let gutter = " ".repeat(line_number.to_string().len());
+        let mut lines = vec![
+            format!("{}: {}", self.label(), self.message),
+            format!(
+                "{}--> {}:{}:{}",
+                gutter,
+                path.display(),
+                line_number,
+                column
+            ),
+            format!("{} |", gutter),
+            format!(
+                "{} | {}",
+                line_number,
+                text[line_start..line_end].trim_end_matches('\r')
+            ),
+            format!(
+                "{} | {}{}",
+                gutter,
+                " ".repeat(column - 1),
+                "^".repeat(width)
+            ),
+        ];
+        if let Some(hint) = &self.hint {
+            lines.push(format!("{} = hint: {}", gutter, hint));
+        }
+        lines.join("\n")
+    }
+
+    fn label(&self) -> &str {
+        match self.severity {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid config: {}", self.message)
+    }
+}
+
+impl std::error::Error for Diagnostic {}
+
+/// Parse and validate config text, None if it is valid.
+pub fn diagnose(text: &str) -> Option<Diagnostic> {
+    let config = match toml::from_str::<Config>(text) {
+        Err(err) => return Some(Diagnostic::from_toml(&err)),
+        Ok(config_v) => config_v,
+    };
+
+    validate_config(&config).err().map(|err| {
+        err.downcast::<Diagnostic>()
+            .unwrap_or_else(|err| Diagnostic::new(err.to_string()))
+    })
+}
+
+/// Span of the value at keys, or of its closest existing parent.
+fn locate(text: &str, keys: &[Key]) -> Option<Range<usize>> {
+    let document = ImDocument::parse(text).ok()?;
+    let mut item = document.as_item();
+    let mut span = None;
+    for key in keys.iter() {
+        let child = match key {
+            Key::Field(field) => item.get(field.as_str()),
+            Key::Index(index) => item.get(*index),
+        };
+        item = match child {
+            None => break,
+            Some(child_v) => child_v,
+        };
+        span = item_span(item).or(span);
+    }
+    span
+}
+
+fn item_span(item: &Item) -> Option<Range<usize>> {
+    match item {
+        Item::None => None,
+        Item::Value(value) => value.span(),
+        Item::Table(table) => table.span(),
+        Item::ArrayOfTables(array) => array.span(),
+    }
+}
+
+fn closest_name<'a>(name: &str, candidates: &[&'a str]) -> Option<&'a str> {
+    candidates
+        .iter()
+        .map(|x| (edit_distance(name, x), *x))
+        .filter(|(distance, _)| *distance <= 2)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, x)| x)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<char>>();
+    let mut row = (0..=b.len()).collect::<Vec<usize>>();
+    for (i, x) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, y) in b.iter().enumerate() {
+            let current = row[j + 1];
+            row[j + 1] = match x == *y {
+                true => previous,
+                false => previous.min(row[j]).min(current) + 1,
+            };
+            previous = current;
+        }
+    }
+    row[b.len()]
+}
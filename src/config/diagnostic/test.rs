@@ -0,0 +1,48 @@
+use std::path::PathBuf;
+
+use super::*;
+
+const CONFIG: &str = r#"[[branch_types]]
+name = "feature"
+create = "feature/{NAME}"
+from = "dev"
+to = [{ name = "dev(", strategy = "merge" }]
+"#;
+
+#[test]
+fn diagnose_t() {
+    let path = PathBuf::from(".git-flow.toml");
+
+    let diagnostic = diagnose(CONFIG).unwrap();
+    assert_eq!(
+        diagnostic.render(&path, CONFIG),
+        r#"error: target branch dev( is not a valid regex
+ --> .git-flow.toml:5:16
+  |
+5 | to = [{ name = "dev(", strategy = "merge" }]
+  |                ^^^^^^"#
+    );
+
+    let text = CONFIG.replace("dev(", "dev").replace(
+        "from = ",
+        "befor_start = { command = \"ls\", args = [] }\nfrom = ",
+    );
+    let diagnostic = diagnose(&text).unwrap();
+    assert_eq!(diagnostic.message, "unknown field `befor_start`");
+    assert_eq!(
+        diagnostic.hint.as_deref(),
+        Some("did you mean `before_start`?")
+    );
+    assert!(diagnostic
+        .render(&path, &text)
+        .contains(" --> .git-flow.toml:4:1\n"));
+
+    assert!(diagnose(&CONFIG.replace("dev(", "dev")).is_none());
+}
+
+#[test]
+fn edit_distance_t() {
+    assert_eq!(edit_distance("befor_start", "before_start"), 1);
+    assert_eq!(edit_distance("form", "from"), 2);
+    assert_eq!(edit_distance("", "to"), 2);
+}
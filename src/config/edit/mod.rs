@@ -0,0 +1,139 @@
+use anyhow::{anyhow, bail, Result};
+use serde::{
+    de::{value, IntoDeserializer},
+    Deserialize,
+};
+use toml_edit::{
+    value, Array, ArrayOfTables, DocumentMut, InlineTable, Item, Table, TableLike, Value,
+};
+
+use super::definition::Strategy;
+
+#[cfg(test)]
+mod test;
+
+const BRANCH_TYPES_KEY: &str = "branch_types";
+const DEFAULT_STRATEGY: &str = "merge";
+
+/// Append a branch type, each of to is BRANCH[:STRATEGY] with merge as the default strategy.
+/// BRANCH may contain `:` itself, the text after the last one is taken as the strategy
+/// if it is a lowercase word, and must then be a known strategy.
+pub fn add_branch_type(
+    text: &str,
+    name: &str,
+    create: &str,
+    from: &str,
+    to: &[String],
+) -> Result<String> {
+    let mut document = text.parse::<DocumentMut>()?;
+    let branch_types = document
+        .entry(BRANCH_TYPES_KEY)
+        .or_insert(Item::ArrayOfTables(ArrayOfTables::new()))
+        .as_array_of_tables_mut()
+        .ok_or(anyhow!("{} is not an array of tables", BRANCH_TYPES_KEY))?;
+    if branch_types.iter().any(|x| is_branch_type(x, name)) {
+        bail!("branch type {} already exists", name);
+    }
+
+    // -- targets --
+    let mut targets = Array::new();
+    for x in to.iter() {
+        let (branch, strategy) = match x.rsplit_once(':') {
+            Some((branch_v, strategy_v)) if is_strategy(strategy_v) => (branch_v, strategy_v),
+            Some((_, strategy_v)) if looks_like_strategy(strategy_v) => bail!(
+                "unknown strategy {}, expected merge, rebase, cherry-pick or pull-request",
+                strategy_v
+            ),
+            _ => (x.as_str(), DEFAULT_STRATEGY),
+        };
+        let mut target = InlineTable::new();
+        target.insert("name", branch.into());
+        target.insert("strategy", strategy.into());
+        targets.push(target);
+    }
+
+    let mut branch_type = Table::new();
+    branch_type.insert("name", value(name));
+    branch_type.insert("create", value(create));
+    branch_type.insert("from", value(from));
+    branch_type.insert("to", value(targets));
+    if !branch_types.is_empty() || !text.trim().is_empty() {
+        branch_type.decor_mut().set_prefix("\n");
+    }
+    branch_types.push(branch_type);
+
+    Ok(document.to_string())
+}
+
+pub fn remove_branch_type(text: &str, name: &str) -> Result<String> {
+    let mut document = text.parse::<DocumentMut>()?;
+    let branch_types = get_branch_types(&mut document)?;
+    let index = branch_types.iter().position(|x| is_branch_type(x, name));
+    match index {
+        None => bail!("branch type {} is not found", name),
+        Some(index_v) => branch_types.remove(index_v),
+    }
+
+    Ok(document.to_string())
+}
+
+/// Set <type>.<field> of a branch type, field may be a dotted path such as tag.branch.
+/// raw is parsed as a TOML value, or taken as a string if it is not one.
+pub fn set_branch_type_field(text: &str, key: &str, raw: &str) -> Result<String> {
+    let (name, field) = key
+        .split_once('.')
+        .ok_or(anyhow!("key {} is not in <type>.<field> format", key))?;
+    let mut document = text.parse::<DocumentMut>()?;
+    let branch_type = get_branch_types(&mut document)?
+        .iter_mut()
+        .find(|x| is_branch_type(x, name))
+        .ok_or(anyhow!("branch type {} is not found", name))?;
+
+    // -- find parent table --
+    let keys = field.split('.').collect::<Vec<&str>>();
+    let (last, parents) = keys.split_last().unwrap();
+    let mut table: &mut dyn TableLike = branch_type;
+    for x in parents.iter() {
+        table = table
+            .entry(x)
+            .or_insert(value(InlineTable::new()))
+            .as_table_like_mut()
+            .ok_or(anyhow!("{} is not a table", x))?;
+    }
+
+    // -- set value, keeping comments around the old one --
+    let new_value = raw.parse::<Value>().unwrap_or_else(|_| raw.into());
+    match table.get_mut(last).and_then(|x| x.as_value_mut()) {
+        None => {
+            table.insert(last, value(new_value));
+        }
+        Some(old_value) => {
+            let decor = old_value.decor().clone();
+            *old_value = new_value;
+            *old_value.decor_mut() = decor;
+        }
+    }
+
+    Ok(document.to_string())
+}
+
+fn get_branch_types(document: &mut DocumentMut) -> Result<&mut ArrayOfTables> {
+    document
+        .get_mut(BRANCH_TYPES_KEY)
+        .and_then(|x| x.as_array_of_tables_mut())
+        .ok_or(anyhow!("no {} array of tables", BRANCH_TYPES_KEY))
+}
+
+fn is_strategy(name: &str) -> bool {
+    let deserializer: value::StrDeserializer<value::Error> = name.into_deserializer();
+    Strategy::deserialize(deserializer).is_ok()
+}
+
+/// A lowercase word such as a misspelled strategy, rather than a part of the branch regex.
+fn looks_like_strategy(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|x| x.is_ascii_lowercase() || x == '-')
+}
+
+fn is_branch_type(table: &Table, name: &str) -> bool {
+    table.get("name").and_then(|x| x.as_str()) == Some(name)
+}
@@ -0,0 +1,85 @@
+use super::*;
+
+const CONFIG: &str = r#"# branch types
+[[branch_types]]
+name = "feature"
+create = "feature/{NAME}"
+# start from dev
+from = "dev"
+to = [{ name = "dev", strategy = "merge" }]
+"#;
+
+#[test]
+fn add_branch_type_t() {
+    let text = add_branch_type(
+        CONFIG,
+        "hotfix",
+        "hotfix/{NAME}",
+        "main",
+        &["main".to_string(), "dev:rebase".to_string()],
+    )
+    .unwrap();
+    assert!(text.starts_with(CONFIG));
+    assert!(text.ends_with(
+        r#"
+[[branch_types]]
+name = "hotfix"
+create = "hotfix/{NAME}"
+from = "main"
+to = [{ name = "main", strategy = "merge" }, { name = "dev", strategy = "rebase" }]
+"#
+    ));
+
+    assert!(add_branch_type(CONFIG, "feature", "f/{NAME}", "dev", &[]).is_err());
+
+    // a regex with `:` is not split unless a strategy follows
+    let text = add_branch_type(
+        CONFIG,
+        "hotfix",
+        "hotfix/{NAME}",
+        "main",
+        &[
+            "release/(?:v1|v2)".to_string(),
+            "(?i:dev):cherry-pick".to_string(),
+        ],
+    )
+    .unwrap();
+    assert!(text.ends_with(
+        r#"to = [{ name = "release/(?:v1|v2)", strategy = "merge" }, { name = "(?i:dev)", strategy = "cherry-pick" }]
+"#
+    ));
+
+    let result = add_branch_type(
+        CONFIG,
+        "hotfix",
+        "hotfix/{NAME}",
+        "main",
+        &["dev:rebse".to_string()],
+    );
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "unknown strategy rebse, expected merge, rebase, cherry-pick or pull-request"
+    );
+}
+
+#[test]
+fn remove_branch_type_t() {
+    let text = add_branch_type(CONFIG, "hotfix", "hotfix/{NAME}", "main", &[]).unwrap();
+    assert_eq!(remove_branch_type(&text, "hotfix").unwrap(), CONFIG);
+    assert!(remove_branch_type(CONFIG, "bugfix").is_err());
+}
+
+#[test]
+fn set_branch_type_field_t() {
+    let text = set_branch_type_field(CONFIG, "feature.from", "main").unwrap();
+    assert!(text.contains("# start from dev\nfrom = \"main\""));
+
+    let text = set_branch_type_field(CONFIG, "feature.forward_port", "true").unwrap();
+    assert!(text.contains("forward_port = true"));
+
+    let text = set_branch_type_field(CONFIG, "feature.tag.branch", "main").unwrap();
+    assert!(text.contains("tag = { branch = \"main\" }"));
+
+    assert!(set_branch_type_field(CONFIG, "feature", "main").is_err());
+    assert!(set_branch_type_field(CONFIG, "bugfix.from", "main").is_err());
+}
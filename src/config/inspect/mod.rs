@@ -0,0 +1,205 @@
+use std::{collections::HashMap, env, path::Path};
+
+use anyhow::Result;
+use regex::Regex;
+
+use super::{
+    definition::{BranchType, Command, Config},
+    diagnostic::Diagnostic,
+};
+use crate::{
+    git::Git,
+    pattern::{branch_regex, Pattern},
+    utils::create_regex,
+};
+
+#[cfg(test)]
+mod test;
+
+/// values tried for placeholders with rules when making sample branch names
+const SAMPLE_VALUES: [&str; 5] = ["sample", "1", "1.0.0", "ABC-1", "a"];
+
+/// Branches and commands of the repo the config is checked against
+pub struct Repo {
+    pub local_branches: Vec<String>,
+    /// names of remote branches, without the remote repo
+    pub remote_branches: Vec<String>,
+    pub has_command: Box<dyn Fn(&str) -> bool>,
+}
+
+impl Repo {
+    pub fn load() -> Result<Repo> {
+        let mut remote_branches = Vec::<String>::new();
+        for repo in Git::get_remote_repos().unwrap_or_default() {
+            remote_branches.extend(Git::get_remote_branches(&repo)?);
+        }
+
+        Ok(Repo {
+            local_branches: Git::get_local_branches()?,
+            remote_branches,
+            has_command: Box::new(command_exists),
+        })
+    }
+}
+
+/// Problems of config which only show up in repo, such as missing branches.
+pub fn inspect_config(config: &Config, repo: &Repo) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::<Diagnostic>::new();
+    let samples = config
+        .branch_types
+        .iter()
+        .map(|x| sample_branches(x, &repo.local_branches))
+        .collect::<Vec<Vec<String>>>();
+
+    for (i, branch_type) in config.branch_types.iter().enumerate() {
+        // -- from exists --
+        let from = branch_regex(&branch_type.from, true).ok();
+        let from_exists = repo
+            .local_branches
+            .iter()
+            .chain(repo.remote_branches.iter())
+            .any(|x| from.as_ref().is_some_and(|y| y.is_match(x)));
+        if !from_exists {
+            diagnostics.push(
+                Diagnostic::new(format!(
+                    "from branch {} of branch type {} is not found",
+                    &branch_type.from, &branch_type.name
+                ))
+                .branch_type(i)
+                .key("from")
+                .hint("create it, or fetch it from the remote repo"),
+            );
+        }
+
+        // -- to matches branches --
+        for (j, target) in branch_type.to.iter().enumerate() {
+            let regex = match branch_regex(&target.name, false) {
+                Err(_) => continue,
+                Ok(regex_v) => regex_v,
+            };
+            let sample = samples[i].iter().find(|x| regex.is_match(x));
+            let diagnostic = match sample {
+                Some(sample_v) => Diagnostic::new(format!(
+                    "target branch {} of branch type {} matches its own task branches",
+                    &target.name, &branch_type.name
+                ))
+                .warning()
+                .hint(format!("{} would be merged into itself", sample_v)),
+                None if repo.local_branches.iter().all(|x| !regex.is_match(x)) => {
+                    Diagnostic::new(format!(
+                        "target branch {} of branch type {} matches no local branch",
+                        &target.name, &branch_type.name
+                    ))
+                    .warning()
+                }
+                None => continue,
+            };
+            diagnostics.push(diagnostic.branch_type(i).key("to").index(j).key("name"));
+        }
+
+        // -- hooks exist --
+        let hooks = [
+            ("before_start", &branch_type.before_start),
+            ("after_start", &branch_type.after_start),
+            ("before_finish", &branch_type.before_finish),
+            ("after_finish", &branch_type.after_finish),
+            ("before_drop", &branch_type.before_drop),
+            ("after_drop", &branch_type.after_drop),
+        ];
+        for (field, hook) in hooks {
+            if let Some(Command { command, .. }) = hook {
+                if !(repo.has_command)(command) {
+                    diagnostics.push(
+                        Diagnostic::new(format!(
+                            "hook command {} of branch type {} is not found",
+                            command, &branch_type.name
+                        ))
+                        .warning()
+                        .branch_type(i)
+                        .key(field)
+                        .key("command")
+                        .hint("install it, or check PATH"),
+                    );
+                }
+            }
+        }
+
+        // -- create does not overlap --
+        for (j, other) in config.branch_types.iter().enumerate().take(i) {
+            let (regex, other_regex) =
+                match (create_regex(branch_type).ok(), create_regex(other).ok()) {
+                    (Some(regex_v), Some(other_regex_v)) => (regex_v, other_regex_v),
+                    _ => continue,
+                };
+            let overlap = samples[i]
+                .iter()
+                .chain(samples[j].iter())
+                .find(|x| regex.is_match(x) && other_regex.is_match(x));
+            if let Some(branch) = overlap {
+                diagnostics.push(
+                    Diagnostic::new(format!(
+                        "create {} of branch type {} overlaps create {} of branch type {}",
+                        &branch_type.create, &branch_type.name, &other.create, &other.name
+                    ))
+                    .warning()
+                    .branch_type(i)
+                    .key("create")
+                    .hint(format!(
+                        "{} matches both, so its branch type has to be specified",
+                        branch
+                    )),
+                );
+            }
+        }
+    }
+
+    diagnostics
+}
+
+/// Whether command is an existing path, or an executable file in PATH.
+pub fn command_exists(command: &str) -> bool {
+    if command.contains('/') || command.contains('\\') {
+        return Path::new(command).is_file();
+    }
+
+    env::var_os("PATH").is_some_and(|paths| {
+        env::split_paths(&paths).any(|x| {
+            x.join(command).is_file()
+                || x.join(format!("{}{}", command, env::consts::EXE_SUFFIX))
+                    .is_file()
+        })
+    })
+}
+
+/// Existing task branches of branch_type, plus one made up from create if possible.
+fn sample_branches(branch_type: &BranchType, branches: &[String]) -> Vec<String> {
+    let mut samples = match create_regex(branch_type) {
+        Err(_) => return Vec::new(),
+        Ok(regex) => branches
+            .iter()
+            .filter(|x| regex.is_match(x))
+            .cloned()
+            .collect::<Vec<String>>(),
+    };
+
+    let pattern = match Pattern::parse(&branch_type.create) {
+        Err(_) => return samples,
+        Ok(pattern_v) => pattern_v,
+    };
+    let mut values = HashMap::<String, String>::new();
+    for key in pattern.placeholders() {
+        let value = SAMPLE_VALUES.iter().find(|x| {
+            branch_type.placeholders.get(key).is_none_or(|rule| {
+                Regex::new(&format!("^(?:{})$", rule)).is_ok_and(|y| y.is_match(x))
+            })
+        });
+        match value {
+            None => return samples,
+            Some(value_v) => values.insert(key.to_string(), value_v.to_string()),
+        };
+    }
+    if let Ok(sample) = pattern.fill(&branch_type.placeholders, &values) {
+        samples.push(sample);
+    }
+    samples
+}
@@ -0,0 +1,79 @@
+use super::*;
+use crate::config::diagnostic::Severity;
+
+fn get_repo() -> Repo {
+    Repo {
+        local_branches: vec![
+            "main".to_string(),
+            "feature/a".to_string(),
+            "feature/ABC-1-b".to_string(),
+        ],
+        remote_branches: vec!["dev".to_string()],
+        has_command: Box::new(|x| x == "git"),
+    }
+}
+
+#[test]
+fn inspect_config_t() {
+    let config: Config = toml::from_str(
+        r#"
+[[branch_types]]
+name = "feature"
+create = "feature/{NAME}"
+from = "dev"
+to = [{ name = "dev", strategy = "merge" }]
+after_start = { command = "git", args = [] }
+
+[[branch_types]]
+name = "ticket"
+create = "feature/{TICKET}-{NAME}"
+from = "develop"
+to = [{ name = "feature/.*", strategy = "merge" }, { name = "staging", strategy = "merge" }]
+after_finish = { command = "missing-command", args = [] }
+placeholders = { TICKET = "[A-Z]+-\\d+" }
+"#,
+    )
+    .unwrap();
+
+    let diagnostics = inspect_config(&config, &get_repo());
+    let errors = diagnostics
+        .iter()
+        .filter(|x| x.severity == Severity::Error)
+        .count();
+    assert_eq!(errors, 1);
+    let messages = diagnostics
+        .into_iter()
+        .map(|x| x.message)
+        .collect::<Vec<String>>();
+    assert_eq!(
+        messages,
+        vec![
+            "target branch dev of branch type feature matches no local branch",
+            "from branch develop of branch type ticket is not found",
+            "target branch feature/.* of branch type ticket matches its own task branches",
+            "target branch staging of branch type ticket matches no local branch",
+            "hook command missing-command of branch type ticket is not found",
+            "create feature/{TICKET}-{NAME} of branch type ticket overlaps create feature/{NAME} of branch type feature",
+        ]
+    );
+}
+
+#[test]
+fn sample_branches_t() {
+    let config: Config = toml::from_str(
+        r#"
+[[branch_types]]
+name = "release"
+create = "release/{NAME}"
+from = "main"
+to = []
+placeholders = { NAME = "\\d+\\.\\d+\\.\\d+" }
+"#,
+    )
+    .unwrap();
+
+    assert_eq!(
+        sample_branches(&config.branch_types[0], &["release/2.0.0".to_string()]),
+        vec!["release/2.0.0", "release/1.0.0"]
+    );
+}
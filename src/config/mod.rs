@@ -1,4 +1,8 @@
 pub mod definition;
-mod path;
+pub mod diagnostic;
+pub mod edit;
+pub mod inspect;
+pub mod path;
 pub mod read;
+pub mod schema;
 pub mod validate;
@@ -1,4 +1,4 @@
-use std::{fs::File, io::Read, path::PathBuf};
+use std::{fs, path::PathBuf};
 
 use anyhow::{bail, Context, Result};
 
@@ -8,32 +8,45 @@ use super::{definition, path, validate::validate_config};
 mod test;
 
 pub fn read_config(config_path: Option<PathBuf>) -> Result<definition::Config> {
+    match read_optional_config(config_path)? {
+        None => bail!("config file is not found"),
+        Some(config) => Ok(config),
+    }
+}
+
+/// Same as read_config, but it is not an error if no default config file exists.
+pub fn read_optional_config(config_path: Option<PathBuf>) -> Result<Option<definition::Config>> {
     // -- get path --
-    let config_path_list = match config_path {
-        Some(config_path_v) => vec![config_path_v],
-        None => path::get_config_path_list().context("unable to get default config path")?,
+    let path = match find_config_path(config_path)? {
+        None => return Ok(None),
+        Some(path_v) => path_v,
     };
 
     // -- read file --
-    let mut config_file = None;
-    for config_path in config_path_list {
-        if let Ok(config_file_v) = File::open(config_path) {
-            config_file = Some(config_file_v);
-            break;
-        }
-    }
-    if config_file.is_none() {
-        bail!("config file is not found");
+    let text = fs::read_to_string(&path)?;
+
+    parse_config(&text).map(Some)
+}
+
+/// The explicit config path, or the first existing default config path.
+pub fn find_config_path(config_path: Option<PathBuf>) -> Result<Option<PathBuf>> {
+    match config_path {
+        Some(config_path_v) => match config_path_v.is_file() {
+            true => Ok(Some(config_path_v)),
+            false => bail!("config file is not found"),
+        },
+        None => Ok(path::get_config_path_list()
+            .context("unable to get default config path")?
+            .into_iter()
+            .find(|x| x.is_file())),
     }
-    let mut text = String::new();
-    config_file.unwrap().read_to_string(&mut text)?;
+}
 
+pub fn parse_config(text: &str) -> Result<definition::Config> {
     // -- parse config --
-    let config = toml::from_str::<definition::Config>(&text).context("unable to parse config")?;
+    let config = toml::from_str::<definition::Config>(text).context("unable to parse config")?;
 
-    // -- validate --
-    match validate_config(&config) {
-        Ok(_) => Ok(config),
-        Err(err) => Err(err.context("config is invalid")),
-    }
+    // -- validate, errors are diagnostics displayed as "invalid config: ..." --
+    validate_config(&config)?;
+    Ok(config)
 }
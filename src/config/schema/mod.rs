@@ -0,0 +1,33 @@
+use schemars::gen::SchemaSettings;
+use serde_json::Value;
+
+use super::definition::Config;
+
+#[cfg(test)]
+mod test;
+
+/// JSON Schema of the config file, for editors like taplo to complete and validate it.
+pub fn config_schema() -> String {
+    // TOML has no null, so optional fields are just not required
+    let generator = SchemaSettings::draft07()
+        .with(|x| x.option_add_null_type = false)
+        .into_generator();
+    let mut schema = serde_json::to_value(generator.into_root_schema_for::<Config>()).unwrap();
+    remove_nulls(&mut schema);
+
+    let mut text = serde_json::to_string_pretty(&schema).unwrap();
+    text.push('\n');
+    text
+}
+
+/// Remove unset optional fields from defaults.
+fn remove_nulls(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            map.retain(|_, x| !x.is_null());
+            map.values_mut().for_each(remove_nulls);
+        }
+        Value::Array(array) => array.iter_mut().for_each(remove_nulls),
+        _ => (),
+    }
+}
@@ -0,0 +1,10 @@
+use super::*;
+
+#[test]
+fn config_schema_t() {
+    // run `git flow config schema > git-flow.schema.json` after changing config definitions
+    assert_eq!(
+        config_schema(),
+        include_str!("../../../git-flow.schema.json")
+    );
+}
@@ -1,7 +1,11 @@
 use anyhow::{bail, Result};
 use regex::Regex;
 
-use super::definition::{Config, BRANCH_NAME_PLACEHOLDER};
+use super::{
+    definition::{Config, Strategy, BASE_PLACEHOLDER, BRANCH_NAME_PLACEHOLDER, NAME_KEY},
+    diagnostic::Diagnostic,
+};
+use crate::pattern::Pattern;
 
 #[cfg(test)]
 mod test;
@@ -10,6 +14,15 @@ pub fn validate_config(config: &Config) -> Result<()> {
     no_duplicate_branch_type(&config)?;
     target_is_valid_regex(&config)?;
     create_is_valid(&config)?;
+    placeholders_are_valid(config)?;
+    name_rule_is_valid_regex(config)?;
+    ticket_is_valid_regex(config)?;
+    pull_request_has_forge(config)?;
+    commit_lint_has_ticket(config)?;
+    version_file_is_valid_regex(config)?;
+    back_merge_is_valid_regex(config)?;
+    sync_is_valid_regex(config)?;
+    forward_port_has_base(config)?;
     Ok(())
 }
 
@@ -23,17 +36,26 @@ fn no_duplicate_branch_type(config: &Config) -> Result<()> {
             let branch_type_j = &branch_types[j];
 
             if branch_type_i.name == branch_type_j.name {
-                bail!(
-                    "invalid config: duplicate branch type name {}",
+                bail!(Diagnostic::new(format!(
+                    "duplicate branch type name {}",
                     &branch_type_i.name
-                );
+                ))
+                .branch_type(i)
+                .key("name")
+                .hint(format!("branch type #{} has the same name", j + 1)));
             }
 
             if branch_type_i.create == branch_type_j.create {
-                bail!(
-                    "invalid config: duplicate branch type create {}",
+                bail!(Diagnostic::new(format!(
+                    "duplicate branch type create {}",
                     &branch_type_i.create
-                );
+                ))
+                .branch_type(i)
+                .key("create")
+                .hint(format!(
+                    "branch type {} has the same create",
+                    &branch_type_j.name
+                )));
             }
         }
     }
@@ -48,11 +70,16 @@ fn target_is_valid_regex(config: &Config) -> Result<()> {
         for j in 0..branch_type.to.len() {
             let target = &branch_type.to[j];
 
-            if Regex::new(&target.name).is_err() {
-                bail!(format!(
-                    "invalid config: target branch {} is not a valid regex",
+            // {BASE} is replaced by an escaped version line
+            if Regex::new(&target.name.replace(BASE_PLACEHOLDER, "base")).is_err() {
+                bail!(Diagnostic::new(format!(
+                    "target branch {} is not a valid regex",
                     &target.name
                 ))
+                .branch_type(i)
+                .key("to")
+                .index(j)
+                .key("name"))
             }
         }
     }
@@ -61,26 +88,242 @@ fn target_is_valid_regex(config: &Config) -> Result<()> {
 }
 
 fn create_is_valid(config: &Config) -> Result<()> {
-    let invalid_creates = config
-        .branch_types
-        .iter()
-        .filter(|x| {
-            x.create
-                .match_indices(BRANCH_NAME_PLACEHOLDER)
-                .map(|x| x.1.to_string())
-                .collect::<Vec<String>>()
-                .len()
-                != 1
-        })
-        .map(|x| format!("{}: {}", x.name, x.create))
-        .collect::<Vec<String>>();
-
-    if invalid_creates.len() > 0 {
-        bail!(
-            "These branch_types have invalid 'create' which should include only one {}:\n{}",
-            BRANCH_NAME_PLACEHOLDER,
-            invalid_creates.join("\n")
-        )
+    let invalid_create = config.branch_types.iter().position(|x| {
+        Pattern::parse(&x.create)
+            .map(|y| y.placeholders().iter().all(|z| *z != NAME_KEY))
+            .unwrap_or(true)
+    });
+
+    if let Some(i) = invalid_create {
+        bail!(Diagnostic::new(format!(
+            "create {} of branch type {} is invalid",
+            &config.branch_types[i].create, &config.branch_types[i].name
+        ))
+        .branch_type(i)
+        .key("create")
+        .hint(format!(
+            "create should include only one {}",
+            BRANCH_NAME_PLACEHOLDER
+        )))
+    }
+
+    Ok(())
+}
+
+fn placeholders_are_valid(config: &Config) -> Result<()> {
+    for (i, branch_type) in config.branch_types.iter().enumerate() {
+        let pattern = Pattern::parse(&branch_type.create)?;
+        for (key, rule) in branch_type.placeholders.iter() {
+            if !pattern.placeholders().contains(&key.as_str()) {
+                bail!(Diagnostic::new(format!(
+                    "placeholder {{{}}} of branch type {} is not found in create",
+                    key, &branch_type.name
+                ))
+                .branch_type(i)
+                .key("placeholders")
+                .key(key)
+                .hint(format!("create is {}", &branch_type.create)))
+            }
+            if Regex::new(rule).is_err() {
+                bail!(Diagnostic::new(format!(
+                    "placeholder {{{}}} rule {} is not a valid regex",
+                    key, rule
+                ))
+                .branch_type(i)
+                .key("placeholders")
+                .key(key))
+            }
+        }
+
+        // rules are combined into the regex matching branch names
+        if pattern.regex(&branch_type.placeholders).is_err() {
+            bail!(Diagnostic::new(format!(
+                "create {} of branch type {} does not make a valid regex",
+                &branch_type.create, &branch_type.name
+            ))
+            .branch_type(i)
+            .key("create")
+            .hint("placeholder rules should not define groups named as placeholders"))
+        }
+    }
+
+    Ok(())
+}
+
+fn name_rule_is_valid_regex(config: &Config) -> Result<()> {
+    for (i, branch_type) in config.branch_types.iter().enumerate() {
+        let rule = &branch_type.name_rule;
+        let regexes = [
+            ("allowed", &rule.allowed),
+            ("ticket_prefix", &rule.ticket_prefix),
+        ];
+        for (field, value) in regexes {
+            if value.as_ref().is_some_and(|x| Regex::new(x).is_err()) {
+                bail!(Diagnostic::new(format!(
+                    "name_rule.{} of branch type {} is not a valid regex",
+                    field, &branch_type.name
+                ))
+                .branch_type(i)
+                .key("name_rule")
+                .key(field))
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn ticket_is_valid_regex(config: &Config) -> Result<()> {
+    for (i, branch_type) in config.branch_types.iter().enumerate() {
+        if let Some(ticket) = &branch_type.ticket {
+            if Regex::new(&ticket.pattern).is_err() {
+                bail!(Diagnostic::new(format!(
+                    "ticket.pattern {} of branch type {} is not a valid regex",
+                    &ticket.pattern, &branch_type.name
+                ))
+                .branch_type(i)
+                .key("ticket")
+                .key("pattern"))
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn pull_request_has_forge(config: &Config) -> Result<()> {
+    if config.forge.is_some() {
+        return Ok(());
+    }
+
+    for (i, branch_type) in config.branch_types.iter().enumerate() {
+        let pull_request = branch_type
+            .to
+            .iter()
+            .position(|x| matches!(x.strategy, Strategy::PullRequest));
+        if let Some(j) = pull_request {
+            bail!(Diagnostic::new(format!(
+                "branch type {} opens pull requests without forge",
+                &branch_type.name
+            ))
+            .branch_type(i)
+            .key("to")
+            .index(j)
+            .key("strategy")
+            .hint("add a [forge] table with kind and repo"))
+        }
+    }
+
+    Ok(())
+}
+
+fn commit_lint_has_ticket(config: &Config) -> Result<()> {
+    for (i, branch_type) in config.branch_types.iter().enumerate() {
+        let require_ticket = branch_type
+            .commit_lint
+            .as_ref()
+            .is_some_and(|x| x.require_ticket);
+        if require_ticket && branch_type.ticket.is_none() {
+            bail!(Diagnostic::new(format!(
+                "commit_lint of branch type {} requires ticket without ticket.pattern",
+                &branch_type.name
+            ))
+            .branch_type(i)
+            .key("commit_lint")
+            .key("require_ticket")
+            .hint("add ticket = { pattern = \"...\" } to the branch type"))
+        }
+    }
+
+    Ok(())
+}
+
+fn version_file_is_valid_regex(config: &Config) -> Result<()> {
+    for (i, branch_type) in config.branch_types.iter().enumerate() {
+        let files = match &branch_type.version_bump {
+            None => continue,
+            Some(version_bump) => &version_bump.files,
+        };
+        for (j, file) in files.iter().enumerate() {
+            if let Some(pattern) = &file.pattern {
+                if Regex::new(pattern).map_or(true, |x| x.captures_len() < 2) {
+                    bail!(Diagnostic::new(format!(
+                        "version file pattern {} is not a valid regex with a group",
+                        pattern
+                    ))
+                    .branch_type(i)
+                    .key("version_bump")
+                    .key("files")
+                    .index(j)
+                    .key("pattern")
+                    .hint("the first group of pattern is replaced by the version"))
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn back_merge_is_valid_regex(config: &Config) -> Result<()> {
+    for (i, branch_type) in config.branch_types.iter().enumerate() {
+        let targets = match &branch_type.back_merge {
+            None => continue,
+            Some(back_merge) => &back_merge.to,
+        };
+        for (j, target) in targets.iter().enumerate() {
+            if Regex::new(target).is_err() {
+                bail!(
+                    Diagnostic::new(format!("back merge target {} is not a valid regex", target))
+                        .branch_type(i)
+                        .key("back_merge")
+                        .key("to")
+                        .index(j)
+                )
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn sync_is_valid_regex(config: &Config) -> Result<()> {
+    let sync = &config.sync;
+    let regexes = [
+        ("protected", &sync.protected),
+        ("include", &sync.include),
+        ("exclude", &sync.exclude),
+    ];
+    for (field, values) in regexes {
+        for (i, value) in values.iter().enumerate() {
+            if Regex::new(value).is_err() {
+                bail!(
+                    Diagnostic::new(format!("sync.{} {} is not a valid regex", field, value))
+                        .key("sync")
+                        .key(field)
+                        .index(i)
+                )
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn forward_port_has_base(config: &Config) -> Result<()> {
+    for (i, branch_type) in config.branch_types.iter().enumerate() {
+        if branch_type.forward_port && !branch_type.from.contains(BASE_PLACEHOLDER) {
+            bail!(Diagnostic::new(format!(
+                "branch type {} enables forward_port without {} in from",
+                &branch_type.name, BASE_PLACEHOLDER
+            ))
+            .branch_type(i)
+            .key("forward_port")
+            .hint(format!(
+                "forward_port needs a parameterized from, e.g. \"support/{}\"",
+                BASE_PLACEHOLDER
+            )))
+        }
     }
 
     Ok(())
@@ -1,4 +1,6 @@
 use super::*;
+use std::path::Path;
+
 use crate::config::read::read_config;
 
 #[test]
@@ -6,3 +8,58 @@ fn validate_config_t() {
     let config_list = read_config(None).unwrap();
     assert_eq!(validate_config(&config_list).is_ok(), true);
 }
+
+#[test]
+fn create_is_valid_regex_t() {
+    let config: Config = toml::from_str(
+        r#"
+[[branch_types]]
+name = "feature"
+create = "feature/{TICKET}-{NAME}"
+from = "dev"
+to = []
+placeholders = { TICKET = "(?P<NAME>x)" }
+"#,
+    )
+    .unwrap();
+    assert!(validate_config(&config).is_err());
+}
+
+#[test]
+fn back_merge_is_valid_regex_t() {
+    let config: Config = toml::from_str(
+        r#"
+[[branch_types]]
+name = "hotfix"
+create = "hotfix/{NAME}"
+from = "main"
+to = [{ name = "main", strategy = "merge" }]
+back_merge = { from = "main", to = ["dev", "release/("] }
+"#,
+    )
+    .unwrap();
+    assert!(validate_config(&config).is_err());
+}
+
+#[test]
+fn commit_lint_has_ticket_t() {
+    let text = r#"
+[[branch_types]]
+name = "feature"
+create = "feature/{NAME}"
+from = "dev"
+to = [{ name = "dev", strategy = "merge" }]
+commit_lint = { require_ticket = true }
+"#;
+    let config: Config = toml::from_str(text).unwrap();
+    let diagnostic = validate_config(&config)
+        .unwrap_err()
+        .downcast::<Diagnostic>()
+        .unwrap();
+    assert_eq!(
+        diagnostic.message,
+        "commit_lint of branch type feature requires ticket without ticket.pattern"
+    );
+    let rendered = diagnostic.render(Path::new(".git-flow.toml"), text);
+    assert!(rendered.contains(" --> .git-flow.toml:7:34\n"));
+}
@@ -0,0 +1,102 @@
+use anyhow::Result;
+use serde::Deserialize;
+use serde_json::json;
+
+use super::{send, ForgeProvider, PullRequest, PullRequestState};
+
+const PAGE_LIMIT: &str = "50";
+
+pub struct Gitea {
+    /// e.g. https://gitea.example.com/api/v1
+    api_url: String,
+    /// owner/name
+    repo: String,
+    token: String,
+}
+
+#[derive(Deserialize)]
+struct Pull {
+    number: u64,
+    html_url: String,
+    state: String,
+    merged: bool,
+    head: PullBranch,
+    base: PullBranch,
+}
+
+#[derive(Deserialize)]
+struct PullBranch {
+    #[serde(rename = "ref")]
+    name: String,
+}
+
+impl From<Pull> for PullRequestState {
+    fn from(pull: Pull) -> Self {
+        PullRequestState {
+            number: pull.number,
+            url: pull.html_url,
+            open: pull.state == "open",
+            merged: pull.merged,
+        }
+    }
+}
+
+impl Gitea {
+    pub fn new(api_url: String, repo: String, token: String) -> Gitea {
+        Gitea {
+            api_url,
+            repo,
+            token,
+        }
+    }
+
+    fn request(&self, method: &str, path: &str) -> ureq::Request {
+        ureq::request(
+            method,
+            &format!("{}/repos/{}{}", self.api_url, self.repo, path),
+        )
+        .set("Authorization", &format!("token {}", self.token))
+    }
+}
+
+impl ForgeProvider for Gitea {
+    fn create_pull_request(&self, pull_request: &PullRequest) -> Result<PullRequestState> {
+        let pull: Pull = send(
+            self.request("POST", "/pulls"),
+            Some(json!({
+                "title": pull_request.title,
+                "body": pull_request.body,
+                "head": pull_request.source_branch,
+                "base": pull_request.target_branch,
+            })),
+        )?;
+        Ok(pull.into())
+    }
+
+    fn find_pull_request(
+        &self,
+        source_branch: &str,
+        target_branch: &str,
+    ) -> Result<Option<PullRequestState>> {
+        // the list api has no head filter, and pulls/{base}/{head} breaks on base names with /
+        for page in 1.. {
+            let pulls: Vec<Pull> = send(
+                self.request("GET", "/pulls")
+                    .query("state", "all")
+                    .query("limit", PAGE_LIMIT)
+                    .query("page", &page.to_string()),
+                None,
+            )?;
+            if pulls.is_empty() {
+                break;
+            }
+            let pull = pulls
+                .into_iter()
+                .find(|x| x.head.name == source_branch && x.base.name == target_branch);
+            if let Some(pull_v) = pull {
+                return Ok(Some(pull_v.into()));
+            }
+        }
+        Ok(None)
+    }
+}
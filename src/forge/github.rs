@@ -0,0 +1,83 @@
+use anyhow::Result;
+use serde::Deserialize;
+use serde_json::json;
+
+use super::{send, ForgeProvider, PullRequest, PullRequestState};
+
+const DEFAULT_API_URL: &str = "https://api.github.com";
+
+pub struct GitHub {
+    api_url: String,
+    /// owner/name
+    repo: String,
+    token: String,
+}
+
+#[derive(Deserialize)]
+struct Pull {
+    number: u64,
+    html_url: String,
+    state: String,
+    merged_at: Option<String>,
+}
+
+impl From<Pull> for PullRequestState {
+    fn from(pull: Pull) -> Self {
+        PullRequestState {
+            number: pull.number,
+            url: pull.html_url,
+            open: pull.state == "open",
+            merged: pull.merged_at.is_some(),
+        }
+    }
+}
+
+impl GitHub {
+    pub fn new(api_url: Option<String>, repo: String, token: String) -> GitHub {
+        GitHub {
+            api_url: api_url.unwrap_or(DEFAULT_API_URL.to_string()),
+            repo,
+            token,
+        }
+    }
+
+    fn request(&self, method: &str, path: &str) -> ureq::Request {
+        ureq::request(
+            method,
+            &format!("{}/repos/{}{}", self.api_url, self.repo, path),
+        )
+        .set("Authorization", &format!("Bearer {}", self.token))
+        .set("Accept", "application/vnd.github+json")
+    }
+}
+
+impl ForgeProvider for GitHub {
+    fn create_pull_request(&self, pull_request: &PullRequest) -> Result<PullRequestState> {
+        let pull: Pull = send(
+            self.request("POST", "/pulls"),
+            Some(json!({
+                "title": pull_request.title,
+                "body": pull_request.body,
+                "head": pull_request.source_branch,
+                "base": pull_request.target_branch,
+            })),
+        )?;
+        Ok(pull.into())
+    }
+
+    fn find_pull_request(
+        &self,
+        source_branch: &str,
+        target_branch: &str,
+    ) -> Result<Option<PullRequestState>> {
+        let owner = self.repo.split('/').next().unwrap_or_default();
+        let pulls: Vec<Pull> = send(
+            self.request("GET", "/pulls")
+                .query("head", &format!("{}:{}", owner, source_branch))
+                .query("base", target_branch)
+                .query("state", "all"),
+            None,
+        )?;
+        Ok(pulls.into_iter().next().map(|x| x.into()))
+    }
+}
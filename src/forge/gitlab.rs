@@ -0,0 +1,85 @@
+use anyhow::Result;
+use serde::Deserialize;
+use serde_json::json;
+
+use super::{send, ForgeProvider, PullRequest, PullRequestState};
+
+const DEFAULT_API_URL: &str = "https://gitlab.com/api/v4";
+
+pub struct GitLab {
+    api_url: String,
+    /// namespace/name
+    repo: String,
+    token: String,
+}
+
+#[derive(Deserialize)]
+struct MergeRequest {
+    iid: u64,
+    web_url: String,
+    state: String,
+}
+
+impl From<MergeRequest> for PullRequestState {
+    fn from(merge_request: MergeRequest) -> Self {
+        PullRequestState {
+            number: merge_request.iid,
+            url: merge_request.web_url,
+            open: merge_request.state == "opened",
+            merged: merge_request.state == "merged",
+        }
+    }
+}
+
+impl GitLab {
+    pub fn new(api_url: Option<String>, repo: String, token: String) -> GitLab {
+        GitLab {
+            api_url: api_url.unwrap_or(DEFAULT_API_URL.to_string()),
+            repo,
+            token,
+        }
+    }
+
+    fn request(&self, method: &str, path: &str) -> ureq::Request {
+        ureq::request(
+            method,
+            &format!(
+                "{}/projects/{}{}",
+                self.api_url,
+                self.repo.replace('/', "%2F"),
+                path
+            ),
+        )
+        .set("PRIVATE-TOKEN", &self.token)
+    }
+}
+
+impl ForgeProvider for GitLab {
+    fn create_pull_request(&self, pull_request: &PullRequest) -> Result<PullRequestState> {
+        let merge_request: MergeRequest = send(
+            self.request("POST", "/merge_requests"),
+            Some(json!({
+                "title": pull_request.title,
+                "description": pull_request.body,
+                "source_branch": pull_request.source_branch,
+                "target_branch": pull_request.target_branch,
+            })),
+        )?;
+        Ok(merge_request.into())
+    }
+
+    fn find_pull_request(
+        &self,
+        source_branch: &str,
+        target_branch: &str,
+    ) -> Result<Option<PullRequestState>> {
+        let merge_requests: Vec<MergeRequest> = send(
+            self.request("GET", "/merge_requests")
+                .query("source_branch", source_branch)
+                .query("target_branch", target_branch)
+                .query("state", "all"),
+            None,
+        )?;
+        Ok(merge_requests.into_iter().next().map(|x| x.into()))
+    }
+}
@@ -0,0 +1,107 @@
+use std::{collections::HashMap, env};
+
+use anyhow::{anyhow, bail, Result};
+use serde::de::DeserializeOwned;
+
+use crate::{
+    config::definition::{ForgeConfig, ForgeKind},
+    pattern::replace_placeholders,
+};
+
+#[cfg(test)]
+mod test;
+
+mod gitea;
+mod github;
+mod gitlab;
+
+pub use gitea::Gitea;
+pub use github::GitHub;
+pub use gitlab::GitLab;
+
+const DEFAULT_TOKEN_ENV: &str = "GIT_FLOW_TOKEN";
+const DEFAULT_TITLE: &str = "Merge {BRANCH} into {TARGET}";
+
+/// Open and query pull requests (merge requests on GitLab).
+pub trait ForgeProvider {
+    fn create_pull_request(&self, pull_request: &PullRequest) -> Result<PullRequestState>;
+    /// The latest pull request from source_branch to target_branch in any state
+    fn find_pull_request(
+        &self,
+        source_branch: &str,
+        target_branch: &str,
+    ) -> Result<Option<PullRequestState>>;
+}
+
+pub struct PullRequest {
+    pub source_branch: String,
+    pub target_branch: String,
+    pub title: String,
+    pub body: String,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct PullRequestState {
+    pub number: u64,
+    pub url: String,
+    /// false once merged or closed
+    pub open: bool,
+    pub merged: bool,
+}
+
+/// Provider of config, the token is read from token_env.
+pub fn get_forge(config: &ForgeConfig) -> Result<Box<dyn ForgeProvider>> {
+    let token_env = config.token_env.as_deref().unwrap_or(DEFAULT_TOKEN_ENV);
+    let token = env::var(token_env).map_err(|_| anyhow!("env {} is not set", token_env))?;
+
+    let api_url = config.api_url.clone();
+    let repo = config.repo.clone();
+    Ok(match config.kind {
+        ForgeKind::GitHub => Box::new(GitHub::new(api_url, repo, token)),
+        ForgeKind::GitLab => Box::new(GitLab::new(api_url, repo, token)),
+        ForgeKind::Gitea => match api_url {
+            None => bail!("forge.api_url is required by gitea"),
+            Some(api_url_v) => Box::new(Gitea::new(api_url_v, repo, token)),
+        },
+    })
+}
+
+/// The pull request from source_branch to target_branch which is still open, closed ones are not reused.
+pub fn find_open_pull_request(
+    forge: &dyn ForgeProvider,
+    source_branch: &str,
+    target_branch: &str,
+) -> Result<Option<PullRequestState>> {
+    Ok(forge
+        .find_pull_request(source_branch, target_branch)?
+        .filter(|x| x.open))
+}
+
+/// Render title and body templates, values are placeholders like {BRANCH}.
+pub fn render_pull_request(
+    title: Option<&str>,
+    body: Option<&str>,
+    values: &HashMap<String, String>,
+) -> (String, String) {
+    (
+        replace_placeholders(title.unwrap_or(DEFAULT_TITLE), values),
+        replace_placeholders(body.unwrap_or_default(), values),
+    )
+}
+
+/// Send a json request, non 2xx responses are errors with the response body.
+fn send<T: DeserializeOwned>(request: ureq::Request, body: Option<serde_json::Value>) -> Result<T> {
+    let result = match body {
+        None => request.call(),
+        Some(body_v) => request.send_json(body_v),
+    };
+    match result {
+        Ok(response) => Ok(response.into_json::<T>()?),
+        Err(ureq::Error::Status(code, response)) => bail!(
+            "forge responded {}: {}",
+            code,
+            response.into_string().unwrap_or_default()
+        ),
+        Err(err) => bail!(err.to_string()),
+    }
+}
@@ -0,0 +1,184 @@
+use std::{
+    io::{BufRead, BufReader, Read, Write},
+    net::TcpListener,
+    thread::{self, JoinHandle},
+};
+
+use super::*;
+
+/// Serve responses in order, returns the url and the received requests like `GET /path\nbody`.
+fn mock_server(responses: Vec<(u16, &'static str)>) -> (String, JoinHandle<Vec<String>>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+
+    let handle = thread::spawn(move || {
+        let mut requests = Vec::<String>::new();
+        for (code, body) in responses {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream);
+
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                if let Some((key, value)) = line.split_once(':') {
+                    if key.eq_ignore_ascii_case("content-length") {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                }
+            }
+            let mut request_body = vec![0; content_length];
+            reader.read_exact(&mut request_body).unwrap();
+
+            let target = request_line.split(' ').take(2).collect::<Vec<&str>>();
+            requests.push(format!(
+                "{} {}\n{}",
+                target[0],
+                target[1],
+                String::from_utf8(request_body).unwrap()
+            ));
+
+            let response = format!(
+                "HTTP/1.1 {} OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                code,
+                body.len(),
+                body
+            );
+            reader.get_mut().write_all(response.as_bytes()).unwrap();
+        }
+        requests
+    });
+
+    (url, handle)
+}
+
+fn pull_request() -> PullRequest {
+    PullRequest {
+        source_branch: "feature/login".to_string(),
+        target_branch: "dev".to_string(),
+        title: "Merge feature/login into dev".to_string(),
+        body: String::new(),
+    }
+}
+
+#[test]
+fn github_t() {
+    let (url, handle) = mock_server(vec![
+        (
+            201,
+            r#"{"number":1,"html_url":"https://github.com/a/b/pull/1","state":"open","merged_at":null}"#,
+        ),
+        (
+            200,
+            r#"[{"number":1,"html_url":"https://github.com/a/b/pull/1","state":"closed","merged_at":"2024-01-01T00:00:00Z"}]"#,
+        ),
+    ]);
+    let github = GitHub::new(Some(url), "a/b".to_string(), "token".to_string());
+
+    let state = github.create_pull_request(&pull_request()).unwrap();
+    assert_eq!(state.number, 1);
+    assert!(state.open && !state.merged);
+    let state = github.find_pull_request("feature/login", "dev").unwrap();
+    assert!(state.is_some_and(|x| !x.open && x.merged));
+
+    let requests = handle.join().unwrap();
+    assert!(requests[0].starts_with("POST /repos/a/b/pulls\n"));
+    assert!(requests[0].contains(r#""head":"feature/login""#));
+    assert!(
+        requests[1].starts_with("GET /repos/a/b/pulls?head=a%3Afeature%2Flogin&base=dev&state=all")
+    );
+}
+
+#[test]
+fn gitlab_t() {
+    let (url, handle) = mock_server(vec![
+        (
+            200,
+            r#"[{"iid":3,"web_url":"https://gitlab.com/a/b/-/merge_requests/3","state":"merged"}]"#,
+        ),
+        (
+            409,
+            r#"{"message":"Another open merge request already exists"}"#,
+        ),
+    ]);
+    let gitlab = GitLab::new(Some(url), "a/b".to_string(), "token".to_string());
+
+    let state = gitlab.find_pull_request("feature/login", "dev").unwrap();
+    assert_eq!(
+        state,
+        Some(PullRequestState {
+            number: 3,
+            url: "https://gitlab.com/a/b/-/merge_requests/3".to_string(),
+            open: false,
+            merged: true,
+        })
+    );
+    let result = gitlab.create_pull_request(&pull_request());
+    assert!(result.unwrap_err().to_string().contains("409"));
+
+    let requests = handle.join().unwrap();
+    assert!(requests[0].starts_with("GET /projects/a%2Fb/merge_requests?"));
+}
+
+#[test]
+fn gitea_t() {
+    let (url, handle) = mock_server(vec![
+        (
+            200,
+            r#"[{"number":5,"html_url":"u5","state":"open","merged":false,"head":{"ref":"feature/other"},"base":{"ref":"dev"}}]"#,
+        ),
+        (
+            200,
+            r#"[{"number":4,"html_url":"u4","state":"closed","merged":true,"head":{"ref":"feature/login"},"base":{"ref":"dev"}}]"#,
+        ),
+        (200, "[]"),
+    ]);
+    let gitea = Gitea::new(url, "a/b".to_string(), "token".to_string());
+
+    let state = gitea
+        .find_pull_request("feature/login", "dev")
+        .unwrap()
+        .unwrap();
+    assert_eq!(state.number, 4);
+    assert!(state.merged);
+    let state = gitea.find_pull_request("feature/missing", "dev").unwrap();
+    assert_eq!(state, None);
+
+    let requests = handle.join().unwrap();
+    assert!(requests[0].starts_with("GET /repos/a/b/pulls?state=all&limit=50&page=1\n"));
+    assert!(requests[1].contains("&page=2\n"));
+}
+
+#[test]
+fn find_open_pull_request_t() {
+    let (url, _) = mock_server(vec![
+        (200, r#"[{"iid":3,"web_url":"u3","state":"opened"}]"#),
+        (200, r#"[{"iid":2,"web_url":"u2","state":"closed"}]"#),
+        (200, "[]"),
+    ]);
+    let gitlab = GitLab::new(Some(url), "a/b".to_string(), "token".to_string());
+
+    let state = find_open_pull_request(&gitlab, "feature/login", "dev").unwrap();
+    assert_eq!(state.map(|x| x.number), Some(3));
+    // closed without merge, a new one is needed
+    let state = find_open_pull_request(&gitlab, "feature/login", "dev").unwrap();
+    assert_eq!(state, None);
+    let state = find_open_pull_request(&gitlab, "feature/login", "dev").unwrap();
+    assert_eq!(state, None);
+}
+
+#[test]
+fn render_pull_request_t() {
+    let values = HashMap::from([
+        ("BRANCH".to_string(), "feature/login".to_string()),
+        ("TARGET".to_string(), "dev".to_string()),
+    ]);
+    let (title, body) = render_pull_request(None, Some("Closes {BRANCH}"), &values);
+    assert_eq!(title, "Merge feature/login into dev");
+    assert_eq!(body, "Closes feature/login");
+}
@@ -17,10 +17,13 @@ impl Git {
             bail!(String::from_utf8(output.stderr).unwrap());
         }
     }
+}
 
-    pub fn del_remote_branch(target_repo: &str, target_branch: &str) -> Result<()> {
+// # create
+impl Git {
+    pub fn create_local_branch(source_branch: &str, target_branch: &str) -> Result<()> {
         let output = Command::new("git")
-            .args(["push", target_repo, "--delete", target_branch])
+            .args(["branch", target_branch, source_branch])
             .output()?;
         if output.status.success() {
             Ok(())
@@ -30,23 +33,47 @@ impl Git {
     }
 }
 
-// # create
+// # check
 impl Git {
-    pub fn create_local_branch(source_branch: &str, target_branch: &str) -> Result<()> {
+    /// Check name is a valid branch name, like `git check-ref-format --branch`
+    pub fn check_branch_name(name: &str) -> Result<()> {
         let output = Command::new("git")
-            .args(["branch", target_branch, source_branch])
+            .args(["check-ref-format", "--branch", name])
             .output()?;
         if output.status.success() {
             Ok(())
         } else {
+            bail!("{} is not a valid branch name", name);
+        }
+    }
+}
+
+// # update
+impl Git {
+    /// Push refspecs in a single push, returns the status of each pushed remote branch.
+    /// leases are <ref>:<commit>, the push of ref is rejected if it is not at commit.
+    pub fn push_refspecs(
+        repo: &str,
+        refspecs: &[String],
+        leases: &[String],
+    ) -> Result<Vec<PushStatus>> {
+        let output = Command::new("git")
+            .args(["push", "--porcelain"])
+            .args(leases.iter().map(|x| format!("--force-with-lease={}", x)))
+            .arg(repo)
+            .args(refspecs)
+            .output()?;
+
+        let statuses = parse_push_porcelain(&String::from_utf8(output.stdout).unwrap());
+        if statuses.is_empty() && !output.status.success() {
             bail!(String::from_utf8(output.stderr).unwrap());
         }
+        Ok(statuses)
     }
 
-    /// Create remote branch from local branch
-    pub fn create_remote_branch(repo: &str, local_branch: &str, remote_branch: &str) -> Result<()> {
+    pub fn set_upstream(branch: &str, upstream: &str) -> Result<()> {
         let output = Command::new("git")
-            .args(["push", repo, &format!("{}:{}", local_branch, remote_branch)])
+            .args(["branch", &format!("--set-upstream-to={}", upstream), branch])
             .output()?;
         if output.status.success() {
             Ok(())
@@ -66,6 +93,48 @@ impl Git {
         Ok(())
     }
 
+    /// The commit branch was created at, from its reflog, None if it has no reflog
+    pub fn get_created_commit(branch: &str) -> Result<Option<String>> {
+        let output = Command::new("git")
+            .args([
+                "reflog",
+                "show",
+                "--format=%H",
+                &format!("refs/heads/{}", branch),
+                "--",
+            ])
+            .output()?;
+        if !output.status.success() {
+            return Ok(None);
+        }
+
+        Ok(String::from_utf8(output.stdout)
+            .unwrap()
+            .lines()
+            .last()
+            .map(|x| x.to_string()))
+    }
+
+    /// None if branch has no upstream
+    pub fn get_upstream(branch: &str) -> Result<Option<String>> {
+        let output = Command::new("git")
+            .args([
+                "for-each-ref",
+                "--format=%(upstream:short)",
+                &format!("refs/heads/{}", branch),
+            ])
+            .output()?;
+        if !output.status.success() {
+            bail!(String::from_utf8(output.stderr).unwrap());
+        }
+
+        let upstream = String::from_utf8(output.stdout).unwrap().trim().to_string();
+        Ok(match upstream.is_empty() {
+            true => None,
+            false => Some(upstream),
+        })
+    }
+
     pub fn get_local_branches() -> Result<Vec<String>> {
         let output = Command::new("git").args(["branch"]).output()?;
         if !output.status.success() {
@@ -87,11 +156,10 @@ impl Git {
         }
 
         let output_str = String::from_utf8(output.stdout).unwrap();
+        // skip symbolic refs like origin/HEAD -> origin/main
         let lines = output_str
             .split('\n')
-            .enumerate()
-            .filter(|(i, x)| *i > 0 && *x != "")
-            .map(|(_, x)| x);
+            .filter(|x| !x.is_empty() && !x.contains(" -> "));
 
         let branch_regex = Regex::new(r"(\S+/\S+)").unwrap();
         let branches = lines
@@ -124,3 +192,30 @@ impl Git {
         Ok(branch_names.collect::<Vec<String>>())
     }
 }
+
+pub struct PushStatus {
+    pub branch: String,
+    /// None if the ref is updated
+    pub error: Option<String>,
+}
+
+/// Parse lines like `<flag>\t<from>:<to>\t<summary>` of `git push --porcelain`
+pub fn parse_push_porcelain(output: &str) -> Vec<PushStatus> {
+    output
+        .split('\n')
+        .filter_map(|x| {
+            let fields = x.split('\t').collect::<Vec<&str>>();
+            if fields.len() < 3 || fields[0].chars().count() != 1 {
+                return None;
+            }
+
+            let to = fields[1].split(':').next_back()?;
+            let branch = to.strip_prefix("refs/heads/").unwrap_or(to).to_string();
+            let error = match fields[0] {
+                "!" => Some(fields[2].to_string()),
+                _ => None,
+            };
+            Some(PushStatus { branch, error })
+        })
+        .collect()
+}
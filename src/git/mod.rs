@@ -1,5 +1,6 @@
 use std::{
     io::{self, Write},
+    path::{Path, PathBuf},
     process::Command,
 };
 
@@ -9,6 +10,10 @@ use anyhow::{bail, Result};
 mod test;
 
 mod branch;
+mod reference;
+mod worktree;
+
+pub use branch::PushStatus;
 
 pub struct Git {}
 
@@ -29,6 +34,180 @@ impl Git {
             false
         }
     }
+
+    /// shared git dir of all worktrees
+    pub fn get_git_dir() -> Result<PathBuf> {
+        let output = Command::new("git")
+            .args(["rev-parse", "--path-format=absolute", "--git-common-dir"])
+            .output()?;
+        if !output.status.success() {
+            bail!(String::from_utf8(output.stderr).unwrap());
+        }
+
+        Ok(PathBuf::from(
+            String::from_utf8(output.stdout).unwrap().trim(),
+        ))
+    }
+
+    /// Whether a merge, rebase, cherry-pick or revert is stopped in the current worktree
+    pub fn is_operation_in_progress() -> Result<bool> {
+        let mut args = vec!["rev-parse", "--path-format=absolute"];
+        for x in [
+            "MERGE_HEAD",
+            "CHERRY_PICK_HEAD",
+            "REVERT_HEAD",
+            "rebase-merge",
+            "rebase-apply",
+        ] {
+            args.extend(["--git-path", x]);
+        }
+        let output = Command::new("git").args(args).output()?;
+        if !output.status.success() {
+            bail!(String::from_utf8(output.stderr).unwrap());
+        }
+
+        Ok(String::from_utf8(output.stdout)
+            .unwrap()
+            .lines()
+            .any(|x| Path::new(x).exists()))
+    }
+
+    /// top level dir of the current worktree
+    pub fn get_root_dir() -> Result<PathBuf> {
+        let output = Command::new("git")
+            .args(["rev-parse", "--show-toplevel"])
+            .output()?;
+        if !output.status.success() {
+            bail!(String::from_utf8(output.stderr).unwrap());
+        }
+
+        Ok(PathBuf::from(
+            String::from_utf8(output.stdout).unwrap().trim(),
+        ))
+    }
+
+    /// modified, staged and untracked files in porcelain format
+    pub fn get_dirty_files() -> Result<Vec<String>> {
+        let output = Command::new("git")
+            .args(["status", "--porcelain"])
+            .output()?;
+        if !output.status.success() {
+            bail!(String::from_utf8(output.stderr).unwrap());
+        }
+
+        let output_str = String::from_utf8(output.stdout).unwrap();
+        Ok(output_str
+            .split('\n')
+            .filter(|x| !x.is_empty())
+            .map(|x| x.to_string())
+            .collect::<Vec<String>>())
+    }
+}
+
+// # config
+impl Git {
+    /// None if key is not set
+    pub fn get_config(key: &str) -> Result<Option<String>> {
+        let output = Command::new("git")
+            .args(["config", "--get", key])
+            .output()?;
+        match output.status.code() {
+            Some(0) => Ok(Some(
+                String::from_utf8(output.stdout).unwrap().trim().to_string(),
+            )),
+            Some(1) => Ok(None),
+            _ => bail!(String::from_utf8(output.stderr).unwrap()),
+        }
+    }
+
+    pub fn set_config(key: &str, value: &str) -> Result<()> {
+        let output = Command::new("git").args(["config", key, value]).output()?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            bail!(String::from_utf8(output.stderr).unwrap());
+        }
+    }
+
+    pub fn unset_config(key: &str) -> Result<()> {
+        let output = Command::new("git")
+            .args(["config", "--unset", key])
+            .output()?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            bail!(String::from_utf8(output.stderr).unwrap());
+        }
+    }
+
+    /// Keys and values of the branch.<branch> section, which is removed with the branch
+    pub fn get_branch_config(branch: &str) -> Result<Vec<(String, String)>> {
+        let output = Command::new("git")
+            .args(["config", "--local", "--get-regexp", r"^branch\."])
+            .output()?;
+        match output.status.code() {
+            Some(0) => (),
+            Some(1) => return Ok(Vec::new()),
+            _ => bail!(String::from_utf8(output.stderr).unwrap()),
+        }
+
+        let prefix = format!("branch.{}.", branch);
+        Ok(String::from_utf8(output.stdout)
+            .unwrap()
+            .lines()
+            .filter_map(|x| x.split_once(' '))
+            .filter(|(x, _)| x.strip_prefix(&prefix).is_some_and(|y| !y.contains('.')))
+            .map(|(x, y)| (x.to_string(), y.to_string()))
+            .collect())
+    }
+}
+
+// # stash
+impl Git {
+    pub fn stash_push(message: &str) -> Result<()> {
+        let output = Command::new("git")
+            .args(["stash", "push", "--include-untracked", "-m", message])
+            .output()?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            bail!(String::from_utf8(output.stderr).unwrap());
+        }
+    }
+
+    pub fn stash_pop() -> Result<()> {
+        let output = Command::new("git").args(["stash", "pop"]).output()?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            bail!(String::from_utf8(output.stderr).unwrap());
+        }
+    }
+}
+
+// # commit
+impl Git {
+    /// Commit changes of paths only
+    pub fn commit_files(paths: &[&str], message: &str) -> Result<()> {
+        let output = Command::new("git")
+            .arg("add")
+            .arg("--")
+            .args(paths)
+            .output()?;
+        if !output.status.success() {
+            bail!(String::from_utf8(output.stderr).unwrap());
+        }
+
+        let output = Command::new("git")
+            .args(["commit", "-m", message, "--"])
+            .args(paths)
+            .output()?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            bail!(String::from_utf8(output.stderr).unwrap());
+        }
+    }
 }
 
 // # combine
@@ -44,6 +223,29 @@ impl Git {
         }
     }
 
+    /// Always create a merge commit with message, git would ignore it on a fast-forward
+    pub fn merge_with_message(source_branch: &str, message: &str) -> Result<()> {
+        let output = Command::new("git")
+            .args(["merge", "--no-ff", "-m", message, source_branch])
+            .output()?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            bail!(String::from_utf8(output.stderr).unwrap());
+        }
+    }
+
+    pub fn merge_ff_only(source_branch: &str) -> Result<()> {
+        let output = Command::new("git")
+            .args(["merge", "--ff-only", source_branch])
+            .output()?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            bail!(String::from_utf8(output.stderr).unwrap());
+        }
+    }
+
     pub fn rebase(base_branch: &str) -> Result<()> {
         let output = Command::new("git").args(["rebase", base_branch]).output()?;
         if output.status.success() {
@@ -79,6 +281,30 @@ impl Git {
         }
     }
 
+    /// move the current branch to commit, keeping uncommitted changes
+    pub fn reset_keep(commit: &str) -> Result<()> {
+        let output = Command::new("git")
+            .args(["reset", "--keep", commit])
+            .output()?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            bail!(String::from_utf8(output.stderr).unwrap());
+        }
+    }
+
+    /// empty if HEAD is detached
+    pub fn get_current_branch() -> Result<String> {
+        let output = Command::new("git")
+            .args(["branch", "--show-current"])
+            .output()?;
+        if !output.status.success() {
+            bail!(String::from_utf8(output.stderr).unwrap());
+        }
+
+        Ok(String::from_utf8(output.stdout).unwrap().trim().to_string())
+    }
+
     /// commits on source_branch but not on target_branch
     pub fn diff_commits(source_branch: &str, target_branch: &str) -> Result<Vec<String>> {
         let output = Command::new("git")
@@ -100,6 +326,112 @@ impl Git {
             .collect::<Vec<String>>())
     }
 
+    /// commits reachable from to but not from, newest first
+    pub fn list_commits(from: Option<&str>, to: &str) -> Result<Vec<String>> {
+        let range = match from {
+            None => to.to_string(),
+            Some(from_v) => format!("{}..{}", from_v, to),
+        };
+        let output = Command::new("git")
+            .args(["log", "--format=%H", &range])
+            .output()?;
+        if !output.status.success() {
+            bail!(String::from_utf8(output.stderr).unwrap());
+        }
+
+        let output_str = String::from_utf8(output.stdout).unwrap();
+        Ok(output_str
+            .split('\n')
+            .filter(|x| !x.is_empty())
+            .map(|x| x.to_string())
+            .collect::<Vec<String>>())
+    }
+
+    /// committer date of commit like 2024-01-31
+    pub fn get_commit_date(commit: &str) -> Result<String> {
+        let output = Command::new("git")
+            .args(["log", "-1", "--format=%cs", commit])
+            .output()?;
+        if !output.status.success() {
+            bail!(String::from_utf8(output.stderr).unwrap());
+        }
+
+        Ok(String::from_utf8(output.stdout).unwrap().trim().to_string())
+    }
+
+    /// committer timestamp of commit in seconds
+    pub fn get_commit_time(commit: &str) -> Result<u64> {
+        let output = Command::new("git")
+            .args(["log", "-1", "--format=%ct", commit])
+            .output()?;
+        if !output.status.success() {
+            bail!(String::from_utf8(output.stderr).unwrap());
+        }
+
+        Ok(String::from_utf8(output.stdout).unwrap().trim().parse()?)
+    }
+
+    /// abbreviated hash and subject of commit
+    pub fn get_commit_summary(commit: &str) -> Result<String> {
+        let output = Command::new("git")
+            .args(["log", "-1", "--format=%h %s", commit])
+            .output()?;
+        if !output.status.success() {
+            bail!(String::from_utf8(output.stderr).unwrap());
+        }
+
+        Ok(String::from_utf8(output.stdout).unwrap().trim().to_string())
+    }
+
+    /// full message of commit
+    pub fn get_commit_message(commit: &str) -> Result<String> {
+        let output = Command::new("git")
+            .args(["log", "-1", "--format=%B", commit])
+            .output()?;
+        if !output.status.success() {
+            bail!(String::from_utf8(output.stderr).unwrap());
+        }
+
+        Ok(String::from_utf8(output.stdout).unwrap().trim().to_string())
+    }
+
+    /// number of commits reachable from rev but not from refs selected by refs_option, e.g. --branches
+    pub fn count_unique_commits(rev: &str, refs_option: &str) -> Result<usize> {
+        let output = Command::new("git")
+            .args(["rev-list", "--count", rev, "--not", refs_option])
+            .output()?;
+        if !output.status.success() {
+            bail!(String::from_utf8(output.stderr).unwrap());
+        }
+
+        Ok(String::from_utf8(output.stdout).unwrap().trim().parse()?)
+    }
+
+    /// number of commits on source_branch but not on target_branch, and the reverse
+    pub fn count_ahead_behind(source_branch: &str, target_branch: &str) -> Result<(usize, usize)> {
+        let output = Command::new("git")
+            .args([
+                "rev-list",
+                "--left-right",
+                "--count",
+                &format!("{}...{}", source_branch, target_branch),
+            ])
+            .output()?;
+        if !output.status.success() {
+            bail!(String::from_utf8(output.stderr).unwrap());
+        }
+
+        let output_str = String::from_utf8(output.stdout).unwrap();
+        let counts = output_str
+            .split_whitespace()
+            .map(|x| x.parse::<usize>())
+            .collect::<Result<Vec<usize>, _>>()?;
+        if counts.len() != 2 {
+            bail!("unexpected output of git rev-list: {}", output_str);
+        }
+        Ok((counts[0], counts[1]))
+    }
+
     /// output commits on source_branch but not on target_branch
     pub fn diff_logs(source_branch: &str, target_branch: &str) -> Result<()> {
         let output = Command::new("git")
@@ -0,0 +1,90 @@
+use std::process::Command;
+
+use anyhow::{bail, Result};
+
+use super::Git;
+
+// # reference
+impl Git {
+    /// full hash of the commit that rev points to
+    pub fn get_commit(rev: &str) -> Result<String> {
+        let output = Command::new("git")
+            .args([
+                "rev-parse",
+                "--verify",
+                "--quiet",
+                &format!("{}^{{commit}}", rev),
+            ])
+            .output()?;
+        if !output.status.success() {
+            bail!("{} is not a valid commit", rev);
+        }
+
+        Ok(String::from_utf8(output.stdout).unwrap().trim().to_string())
+    }
+
+    pub fn update_ref(ref_name: &str, commit: &str) -> Result<()> {
+        let output = Command::new("git")
+            .args(["update-ref", ref_name, commit])
+            .output()?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            bail!(String::from_utf8(output.stderr).unwrap());
+        }
+    }
+
+    /// Create ref_name at commit, fails if ref_name exists
+    pub fn create_ref(ref_name: &str, commit: &str) -> Result<()> {
+        let output = Command::new("git")
+            .args(["update-ref", ref_name, commit, ""])
+            .output()?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            bail!(String::from_utf8(output.stderr).unwrap());
+        }
+    }
+
+    pub fn delete_ref(ref_name: &str) -> Result<()> {
+        let output = Command::new("git")
+            .args(["update-ref", "-d", ref_name])
+            .output()?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            bail!(String::from_utf8(output.stderr).unwrap());
+        }
+    }
+}
+
+// # tag
+impl Git {
+    /// The latest tag reachable from rev, None if there is no tag
+    pub fn get_latest_tag(rev: &str) -> Result<Option<String>> {
+        let output = Command::new("git")
+            .args(["describe", "--tags", "--abbrev=0", rev])
+            .output()?;
+        if !output.status.success() {
+            return Ok(None);
+        }
+
+        Ok(Some(
+            String::from_utf8(output.stdout).unwrap().trim().to_string(),
+        ))
+    }
+
+    /// Tag rev, the tag is annotated if message is set
+    pub fn create_tag(name: &str, rev: &str, message: Option<&str>) -> Result<()> {
+        let args = match message {
+            None => vec!["tag", name, rev],
+            Some(message_v) => vec!["tag", "-a", name, "-m", message_v, rev],
+        };
+        let output = Command::new("git").args(args).output()?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            bail!(String::from_utf8(output.stderr).unwrap());
+        }
+    }
+}
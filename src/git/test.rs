@@ -1,4 +1,6 @@
-use super::*;
+use std::env;
+
+use super::{branch::parse_push_porcelain, *};
 
 #[test]
 fn has_git_t() {
@@ -54,12 +56,6 @@ fn create_local_branch_t() {
     assert_eq!(result.is_ok(), false)
 }
 
-#[test]
-fn create_remote_branch_t() {
-    let result = Git::create_remote_branch("test", "main", "main");
-    assert_eq!(result.is_ok(), false)
-}
-
 #[test]
 fn get_local_branches_t() {
     let result = Git::get_local_branches().unwrap();
@@ -89,7 +85,118 @@ fn get_remote_repos() {
 }
 
 #[test]
-fn del_remote_branch_t() {
-    let result = Git::del_remote_branch("test", "main");
+fn push_refspecs_t() {
+    let lease = "refs/heads/main:".to_string();
+    let result = Git::push_refspecs("test", &[":refs/heads/main".to_string()], &[lease]);
     assert_eq!(result.is_ok(), false);
 }
+
+#[test]
+fn get_current_branch_t() {
+    Git::get_current_branch().unwrap();
+}
+
+#[test]
+fn add_worktree_t() {
+    let result = Git::add_worktree(&env::temp_dir().join("git-flow-test"), "undefined");
+    assert!(result.is_err());
+}
+
+#[test]
+fn get_dirty_files_t() {
+    Git::get_dirty_files().unwrap();
+}
+
+#[test]
+fn get_commit_t() {
+    assert!(Git::get_commit("HEAD").is_ok());
+    assert!(Git::get_commit("undefined").is_err());
+}
+
+#[test]
+fn update_ref_t() {
+    let result = Git::update_ref("refs/heads/undefined", "undefined");
+    assert!(result.is_err());
+}
+
+#[test]
+fn get_git_dir_t() {
+    assert!(Git::get_git_dir().unwrap().is_dir());
+}
+
+#[test]
+fn count_unique_commits_t() {
+    assert_eq!(Git::count_unique_commits("HEAD", "HEAD").unwrap(), 0);
+}
+
+#[test]
+fn count_ahead_behind_t() {
+    assert_eq!(Git::count_ahead_behind("HEAD", "HEAD").unwrap(), (0, 0));
+}
+
+#[test]
+fn merge_ff_only_t() {
+    let result = Git::merge_ff_only("undefined");
+    assert!(result.is_err());
+}
+
+#[test]
+fn get_upstream_t() {
+    assert_eq!(Git::get_upstream("undefined").unwrap(), None);
+}
+
+#[test]
+fn set_upstream_t() {
+    let result = Git::set_upstream("undefined", "undefined/undefined");
+    assert!(result.is_err());
+}
+
+#[test]
+fn parse_push_porcelain_t() {
+    let output = "To ../remote.git\n*\trefs/heads/a:refs/heads/a\t[new branch]\n-\t:refs/heads/b\t[deleted]\n!\trefs/heads/c:refs/heads/c\t[rejected] (non-fast-forward)\nDone\n";
+    let statuses = parse_push_porcelain(output);
+    assert_eq!(statuses.len(), 3);
+    assert_eq!(statuses[1].branch, "b");
+    assert_eq!(statuses[1].error, None);
+    assert_eq!(
+        statuses[2].error.as_deref(),
+        Some("[rejected] (non-fast-forward)")
+    );
+}
+
+#[test]
+fn get_commit_time_t() {
+    assert!(Git::get_commit_time("HEAD").unwrap() > 0);
+}
+
+#[test]
+fn get_commit_message_t() {
+    assert!(!Git::get_commit_message("HEAD").unwrap().is_empty());
+}
+
+#[test]
+fn list_commits_t() {
+    let commits = Git::list_commits(Some("HEAD~1"), "HEAD").unwrap();
+    assert_eq!(commits.len(), 1);
+}
+
+#[test]
+fn get_commit_date_t() {
+    assert_eq!(Git::get_commit_date("HEAD").unwrap().len(), 10);
+}
+
+#[test]
+fn get_config_t() {
+    assert_eq!(Git::get_config("git-flow.undefined").unwrap(), None);
+}
+
+#[test]
+fn check_branch_name_t() {
+    assert!(Git::check_branch_name("feature/login").is_ok());
+    assert!(Git::check_branch_name("feature/My Feature!").is_err());
+}
+
+#[test]
+fn get_root_dir_t() {
+    assert!(Git::get_root_dir().unwrap().join("Cargo.toml").exists());
+}
@@ -0,0 +1,34 @@
+use std::{path::Path, process::Command};
+
+use anyhow::{bail, Result};
+
+use super::Git;
+
+// # worktree
+impl Git {
+    pub fn add_worktree(path: &Path, target_branch: &str) -> Result<()> {
+        let output = Command::new("git")
+            .arg("worktree")
+            .arg("add")
+            .arg(path)
+            .arg(target_branch)
+            .output()?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            bail!(String::from_utf8(output.stderr).unwrap());
+        }
+    }
+
+    pub fn remove_worktree(path: &Path) -> Result<()> {
+        let output = Command::new("git")
+            .args(["worktree", "remove", "--force"])
+            .arg(path)
+            .output()?;
+        if output.status.success() {
+            Ok(())
+        } else {
+            bail!(String::from_utf8(output.stderr).unwrap());
+        }
+    }
+}
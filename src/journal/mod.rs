@@ -0,0 +1,203 @@
+use std::{
+    env,
+    fs::{self, OpenOptions},
+    io::Write,
+    path::PathBuf,
+    process,
+    sync::OnceLock,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::{bail, Result};
+
+use crate::{echo::Echo, git::Git};
+
+#[cfg(test)]
+mod test;
+
+const EMPTY: &str = "-";
+/// ref_name prefix of git config entries, such as the base of a task branch
+const CONFIG_PREFIX: &str = "config:";
+
+/// A ref change made by git-flow.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    /// one operation per git-flow invocation
+    pub operation: String,
+    /// the command line of the operation
+    pub command: String,
+    /// set if ref_name is a branch on this remote repo
+    pub remote: Option<String>,
+    pub ref_name: String,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+impl Entry {
+    pub fn parse(line: &str) -> Result<Entry> {
+        let fields = line.split('\t').collect::<Vec<&str>>();
+        if fields.len() != 6 {
+            bail!("invalid journal entry: {}", line);
+        }
+
+        let optional = |x: &str| match x {
+            EMPTY => None,
+            _ => Some(x.to_string()),
+        };
+        Ok(Entry {
+            operation: fields[0].to_string(),
+            command: fields[1].to_string(),
+            remote: optional(fields[2]),
+            ref_name: fields[3].to_string(),
+            before: optional(fields[4]),
+            after: optional(fields[5]),
+        })
+    }
+
+    /// The git config key if the entry is a config change, before and after are its values then.
+    pub fn config_key(&self) -> Option<&str> {
+        self.ref_name.strip_prefix(CONFIG_PREFIX)
+    }
+
+    pub fn to_line(&self) -> String {
+        [
+            self.operation.as_str(),
+            self.command.as_str(),
+            self.remote.as_deref().unwrap_or(EMPTY),
+            self.ref_name.as_str(),
+            self.before.as_deref().unwrap_or(EMPTY),
+            self.after.as_deref().unwrap_or(EMPTY),
+        ]
+        .join("\t")
+    }
+}
+
+pub struct Journal {}
+
+impl Journal {
+    /// Record a change of a local branch, after is read from the current branch tip.
+    pub fn record_branch(branch: &str, before: Option<String>) {
+        Journal::record_ref(&format!("refs/heads/{}", branch), before);
+    }
+
+    /// Record a change of a local ref, after is read from the current ref.
+    pub fn record_ref(ref_name: &str, before: Option<String>) {
+        let after = Git::get_commit(ref_name).ok();
+        Journal::record(None, ref_name, before, after);
+    }
+
+    /// Record a change of a git config value, after is read from the current config.
+    pub fn record_config(key: &str, before: Option<String>) {
+        let after = Git::get_config(key).ok().flatten();
+        Journal::record(None, &format!("{}{}", CONFIG_PREFIX, key), before, after);
+    }
+
+    /// Record the removal of a deleted branch's config, read by Git::get_branch_config before deleting.
+    pub fn record_branch_config(config: Vec<(String, String)>) {
+        for (key, value) in config {
+            Journal::record_config(&key, Some(value));
+        }
+    }
+
+    /// Record a change of a branch on remote repo.
+    pub fn record_remote_branch(
+        repo: &str,
+        branch: &str,
+        before: Option<String>,
+        after: Option<String>,
+    ) {
+        Journal::record(Some(repo.to_string()), branch, before, after);
+    }
+
+    fn record(
+        remote: Option<String>,
+        ref_name: &str,
+        before: Option<String>,
+        after: Option<String>,
+    ) {
+        if before == after {
+            return;
+        }
+
+        let entry = Entry {
+            operation: operation_id().to_string(),
+            command: format!(
+                "git flow {}",
+                env::args().skip(1).collect::<Vec<String>>().join(" ")
+            )
+            .replace('\t', " "),
+            remote,
+            ref_name: ref_name.to_string(),
+            before,
+            after,
+        };
+        if let Err(err) = Journal::append(&entry) {
+            Echo::warning(format!("unable to record {} in journal: {}", ref_name, err));
+        }
+    }
+
+    fn append(entry: &Entry) -> Result<()> {
+        let path = journal_path()?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        writeln!(file, "{}", entry.to_line())?;
+        Ok(())
+    }
+
+    pub fn read() -> Result<Vec<Entry>> {
+        let path = journal_path()?;
+        if !path.exists() {
+            return Ok(Vec::new());
+        }
+
+        fs::read_to_string(path)?
+            .split('\n')
+            .filter(|x| !x.is_empty())
+            .map(Entry::parse)
+            .collect()
+    }
+
+    pub fn remove_operations(operations: &[String]) -> Result<()> {
+        let text = Journal::read()?
+            .iter()
+            .filter(|x| !operations.contains(&x.operation))
+            .map(|x| format!("{}\n", x.to_line()))
+            .collect::<String>();
+        fs::write(journal_path()?, text)?;
+        Ok(())
+    }
+}
+
+/// Group entries by operation, in order of their first entry.
+pub fn group_operations(entries: &[Entry]) -> Vec<Vec<Entry>> {
+    let mut operations: Vec<Vec<Entry>> = Vec::new();
+    for entry in entries.iter() {
+        match operations
+            .iter_mut()
+            .find(|x| x[0].operation == entry.operation)
+        {
+            Some(operation) => operation.push(entry.clone()),
+            None => operations.push(vec![entry.clone()]),
+        }
+    }
+    operations
+}
+
+fn operation_id() -> &'static str {
+    static OPERATION_ID: OnceLock<String> = OnceLock::new();
+
+    OPERATION_ID.get_or_init(|| {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|x| x.as_millis())
+            .unwrap_or_default();
+        format!("{}-{}", timestamp, process::id())
+    })
+}
+
+fn journal_path() -> Result<PathBuf> {
+    Ok(Git::get_git_dir()?.join("git-flow/journal"))
+}
@@ -0,0 +1,39 @@
+use super::*;
+
+#[test]
+fn entry_t() {
+    let entry = Entry {
+        operation: "1-1".to_string(),
+        command: "git-flow finish feature/a".to_string(),
+        remote: None,
+        ref_name: "refs/heads/feature/a".to_string(),
+        before: Some("abc".to_string()),
+        after: None,
+    };
+    assert_eq!(
+        entry.to_line(),
+        "1-1\tgit-flow finish feature/a\t-\trefs/heads/feature/a\tabc\t-"
+    );
+    assert_eq!(Entry::parse(&entry.to_line()).unwrap(), entry);
+    assert!(Entry::parse("1-1\tgit-flow").is_err());
+}
+
+#[test]
+fn config_key_t() {
+    let entry = Entry::parse("1\tc\t-\tconfig:branch.a.git-flow-base\t1.x\t-").unwrap();
+    assert_eq!(entry.config_key(), Some("branch.a.git-flow-base"));
+    let entry = Entry::parse("1\tc\t-\trefs/heads/a\t-\tx").unwrap();
+    assert_eq!(entry.config_key(), None);
+}
+
+#[test]
+fn group_operations_t() {
+    let entries = ["1\tc\t-\ta\t-\tx", "2\tc\t-\tb\t-\tx", "1\tc\t-\tc\t-\tx"]
+        .iter()
+        .map(|x| Entry::parse(x).unwrap())
+        .collect::<Vec<Entry>>();
+    let operations = group_operations(&entries);
+    assert_eq!(operations.len(), 2);
+    assert_eq!(operations[0].len(), 2);
+    assert_eq!(operations[1][0].ref_name, "b");
+}
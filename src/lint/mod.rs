@@ -0,0 +1,75 @@
+use anyhow::Result;
+use regex::Regex;
+
+use crate::config::definition::{CommitLint, TicketRule};
+
+#[cfg(test)]
+mod test;
+
+/// Header of a conventional commit like `feat(ui)!: login page`
+#[derive(Debug, PartialEq)]
+pub struct Header {
+    pub commit_type: String,
+    pub scope: Option<String>,
+    pub breaking: bool,
+    pub subject: String,
+}
+
+/// None if header is not a conventional commit header.
+pub fn parse_header(header: &str) -> Option<Header> {
+    let header_regex = Regex::new(
+        r"^(?P<type>[a-zA-Z]+)(?:\((?P<scope>[^()]+)\))?(?P<breaking>!)?: (?P<subject>\S.*)$",
+    )
+    .unwrap();
+    header_regex.captures(header).map(|captures| Header {
+        commit_type: captures["type"].to_string(),
+        scope: captures.name("scope").map(|x| x.as_str().to_string()),
+        breaking: captures.name("breaking").is_some(),
+        subject: captures["subject"].to_string(),
+    })
+}
+
+/// Problems of a commit message, empty if it follows rule.
+///
+/// Merge commits created by git are not linted.
+pub fn lint_message(
+    message: &str,
+    rule: &CommitLint,
+    ticket: Option<&TicketRule>,
+) -> Result<Vec<String>> {
+    let header = message.lines().next().unwrap_or_default();
+    if header.starts_with("Merge ") {
+        return Ok(Vec::new());
+    }
+
+    let mut problems = Vec::<String>::new();
+    if let Some(max_header_length) = rule.max_header_length {
+        if header.chars().count() > max_header_length {
+            problems.push(format!("header is longer than {}", max_header_length));
+        }
+    }
+
+    match parse_header(header) {
+        None => problems.push("header is not like type(scope): subject".to_string()),
+        Some(header_v) => {
+            if !rule.types.is_empty() && !rule.types.contains(&header_v.commit_type) {
+                problems.push(format!("type {} is not allowed", header_v.commit_type));
+            }
+            if let Some(scope) = header_v.scope {
+                if !rule.scopes.is_empty() && !rule.scopes.contains(&scope) {
+                    problems.push(format!("scope {} is not allowed", scope));
+                }
+            }
+        }
+    }
+
+    if rule.require_ticket {
+        if let Some(ticket_v) = ticket {
+            if !Regex::new(&ticket_v.pattern)?.is_match(message) {
+                problems.push("no ticket reference".to_string());
+            }
+        }
+    }
+
+    Ok(problems)
+}
@@ -0,0 +1,57 @@
+use super::*;
+
+fn rule() -> CommitLint {
+    CommitLint {
+        types: vec!["feat".to_string(), "fix".to_string()],
+        scopes: vec!["ui".to_string()],
+        max_header_length: Some(30),
+        require_ticket: true,
+    }
+}
+
+fn ticket() -> TicketRule {
+    TicketRule {
+        pattern: "[A-Z]+-\\d+".to_string(),
+        required: false,
+    }
+}
+
+#[test]
+fn parse_header_t() {
+    assert_eq!(
+        parse_header("feat(ui)!: login page"),
+        Some(Header {
+            commit_type: "feat".to_string(),
+            scope: Some("ui".to_string()),
+            breaking: true,
+            subject: "login page".to_string(),
+        })
+    );
+    assert_eq!(parse_header("login page"), None);
+}
+
+#[test]
+fn lint_message_t() {
+    let lint = |x: &str| lint_message(x, &rule(), Some(&ticket())).unwrap();
+
+    assert!(lint("feat(ui): login page\n\nABC-12").is_empty());
+    assert!(lint("fix!: ABC-12 crash").is_empty());
+    assert!(lint("Merge branch 'dev'").is_empty());
+    assert_eq!(
+        lint("docs: ABC-12 readme"),
+        vec!["type docs is not allowed"]
+    );
+    assert_eq!(
+        lint("feat(api): ABC-12 login"),
+        vec!["scope api is not allowed"]
+    );
+    assert_eq!(lint("feat: login"), vec!["no ticket reference"]);
+    assert_eq!(
+        lint("update readme ABC-12"),
+        vec!["header is not like type(scope): subject"]
+    );
+    assert_eq!(
+        lint("feat: ABC-12 a very long login page header"),
+        vec!["header is longer than 30"]
+    );
+}
@@ -1,14 +1,22 @@
 use clap::Parser;
-use cli::{Args, Command};
+use cli::{Args, Command, ConfigAction};
 use echo::Echo;
-use utils::{env_valid, get_branch_type_name};
+use git::Git;
+use utils::{env_valid, get_branch_type_name, pop_stash, resolve_base, stash_changes};
 
+mod changelog;
 mod cli;
 mod command;
 mod config;
 mod echo;
+mod forge;
 mod git;
+mod journal;
+mod lint;
+mod pattern;
+mod ticket;
 mod utils;
+mod version;
 
 #[tokio::main]
 async fn main() {
@@ -16,8 +24,34 @@ async fn main() {
 
     match &args.command {
         Command::List => command::list::list_branch_types(args.config),
-        Command::Check { file_path } => command::check::check_config(file_path.clone()),
-        Command::Sync { target, strategy } => {
+        Command::Check { file_path, repo } => {
+            if *repo && !env_valid() {
+                return;
+            }
+
+            command::check::check_config(file_path.clone().or(args.config), *repo)
+        }
+        Command::Config { action } => match action {
+            ConfigAction::Show => command::config::show_config(args.config),
+            ConfigAction::Path => command::config::show_config_paths(args.config),
+            ConfigAction::Schema => command::config::show_schema(),
+            ConfigAction::AddType {
+                name,
+                create,
+                from,
+                to,
+            } => command::config::add_type(name, create, from, to, args.config),
+            ConfigAction::RemoveType { name } => command::config::remove_type(name, args.config),
+            ConfigAction::Set { key, value } => command::config::set_field(key, value, args.config),
+        },
+        Command::Sync {
+            target,
+            strategy,
+            yes,
+            only,
+            types,
+            remote,
+        } => {
             if !env_valid() {
                 return;
             }
@@ -25,62 +59,207 @@ async fn main() {
             command::sync::sync_repo_branches(
                 target.clone(),
                 strategy.clone().unwrap_or(cli::SyncStrategy::Increment),
+                *yes,
+                only.clone(),
+                types.clone(),
+                remote.clone(),
+                args.config,
             );
         }
         Command::Start {
             branch_name,
             branch_type,
+            vars,
+            base,
+            ticket,
+            autostash,
         } => {
             if !env_valid() {
                 return;
             }
 
-            match get_branch_type_name(branch_name.clone(), branch_type.clone(), args.config) {
+            let resolved = get_branch_type_name(
+                branch_name.clone(),
+                branch_type.clone(),
+                vars.clone(),
+                false,
+                args.config,
+            )
+            .and_then(|(branch_name, branch_type)| {
+                let (branch_type, _) = resolve_base(&branch_name, branch_type, base.clone())?;
+                Ok((branch_name, branch_type))
+            });
+            match resolved {
                 Err(err) => Echo::error(err.to_string()),
                 Ok((branch_name, branch_type)) => {
-                    command::start::start_task(branch_name, branch_type);
+                    let stashed = match stash_changes(*autostash) {
+                        Err(_) => return,
+                        Ok(stashed_v) => stashed_v,
+                    };
+
+                    command::start::start_task(
+                        branch_name,
+                        branch_type,
+                        base.clone(),
+                        ticket.clone(),
+                    );
+
+                    if stashed {
+                        pop_stash(None);
+                    }
                 }
             }
         }
         Command::Finish {
             branch_name,
             branch_type,
+            vars,
+            base,
+            worktree,
+            check_pr,
+            autostash,
         } => {
             if !env_valid() {
                 return;
             }
 
-            match get_branch_type_name(branch_name.clone(), branch_type.clone(), args.config) {
+            let resolved = get_branch_type_name(
+                branch_name.clone(),
+                branch_type.clone(),
+                vars.clone(),
+                true,
+                args.config.clone(),
+            )
+            .and_then(|(branch_name, branch_type)| {
+                let (branch_type, forward_lines) =
+                    resolve_base(&branch_name, branch_type, base.clone())?;
+                Ok((branch_name, branch_type, forward_lines))
+            });
+            match resolved {
                 Err(err) => Echo::error(err.to_string()),
-                Ok((branch_name, branch_type)) => {
-                    command::finish::finish_task(branch_name, branch_type);
+                Ok((branch_name, branch_type, forward_lines)) => {
+                    // worktree mode does not touch the current checkout
+                    let stashed = match *worktree {
+                        true => false,
+                        false => match stash_changes(*autostash) {
+                            Err(_) => return,
+                            Ok(stashed_v) => stashed_v,
+                        },
+                    };
+                    let original_branch = Git::get_current_branch().ok();
+
+                    command::finish::finish_task(
+                        branch_name,
+                        branch_type,
+                        *worktree,
+                        forward_lines,
+                        *check_pr,
+                        args.config,
+                    );
+
+                    if stashed {
+                        pop_stash(original_branch.as_deref());
+                    }
                 }
             }
         }
         Command::Drop {
             branch_name,
             branch_type,
+            vars,
+            base,
+            autostash,
+            force,
+            archive,
         } => {
             if !env_valid() {
                 return;
             }
 
-            match get_branch_type_name(branch_name.clone(), branch_type.clone(), args.config) {
+            let resolved = get_branch_type_name(
+                branch_name.clone(),
+                branch_type.clone(),
+                vars.clone(),
+                true,
+                args.config,
+            )
+            .and_then(|(branch_name, branch_type)| {
+                let (branch_type, _) = resolve_base(&branch_name, branch_type, base.clone())?;
+                Ok((branch_name, branch_type))
+            });
+            match resolved {
                 Err(err) => Echo::error(err.to_string()),
                 Ok((branch_name, branch_type)) => {
-                    command::drop::drop_task(branch_name, branch_type);
+                    let stashed = match stash_changes(*autostash) {
+                        Err(_) => return,
+                        Ok(stashed_v) => stashed_v,
+                    };
+                    let original_branch = Git::get_current_branch().ok();
+
+                    command::drop::drop_task(branch_name, branch_type, *force, archive.clone());
+
+                    if stashed {
+                        pop_stash(original_branch.as_deref());
+                    }
                 }
             }
         }
+        Command::Restore { branch_name } => {
+            if !env_valid() {
+                return;
+            }
+
+            command::restore::restore_task(branch_name.clone());
+        }
+        Command::Prune {
+            days,
+            remote,
+            yes,
+            run_hooks,
+        } => {
+            if !env_valid() {
+                return;
+            }
+
+            command::prune::prune_branches(*days, remote.clone(), *yes, *run_hooks, args.config);
+        }
+        Command::Changelog { from, to } => {
+            if !env_valid() {
+                return;
+            }
+
+            command::changelog::print_changelog(from.clone(), to);
+        }
+        Command::Status => {
+            if !env_valid() {
+                return;
+            }
+
+            command::status::show_status(args.config);
+        }
+        Command::Undo { steps } => {
+            if !env_valid() {
+                return;
+            }
+
+            command::undo::undo_operations(*steps);
+        }
         Command::Track {
             branch_name,
             branch_type,
+            vars,
         } => {
             if !env_valid() {
                 return;
             }
 
-            match get_branch_type_name(branch_name.clone(), branch_type.clone(), args.config) {
+            match get_branch_type_name(
+                branch_name.clone(),
+                branch_type.clone(),
+                vars.clone(),
+                true,
+                args.config,
+            ) {
                 Err(err) => Echo::error(err.to_string()),
                 Ok((branch_name, branch_type)) => {
                     command::track::track_task(branch_name, branch_type);
@@ -0,0 +1,155 @@
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::{bail, Result};
+use regex::Regex;
+
+use crate::config::definition::BASE_PLACEHOLDER;
+
+#[cfg(test)]
+mod test;
+
+mod rule;
+
+pub use rule::{normalize_name, validate_name};
+
+/// Default regex of a placeholder without rule.
+const DEFAULT_RULE: &str = ".*";
+
+#[derive(Debug, Clone, PartialEq)]
+enum Part {
+    Literal(String),
+    Placeholder(String),
+}
+
+/// A branch name pattern like `feature/{TICKET}-{NAME}`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Pattern {
+    parts: Vec<Part>,
+}
+
+impl Pattern {
+    pub fn parse(pattern: &str) -> Result<Pattern> {
+        let placeholder_regex = Regex::new(r"\{([A-Z][A-Z0-9_]*)\}").unwrap();
+
+        let mut parts = Vec::<Part>::new();
+        let mut last = 0;
+        for captures in placeholder_regex.captures_iter(pattern) {
+            let matched = captures.get(0).unwrap();
+            let name = captures.get(1).unwrap().as_str().to_string();
+            if parts.contains(&Part::Placeholder(name.clone())) {
+                bail!("duplicate placeholder {{{}}} in {}", name, pattern);
+            }
+
+            if matched.start() > last {
+                parts.push(Part::Literal(pattern[last..matched.start()].to_string()));
+            }
+            parts.push(Part::Placeholder(name));
+            last = matched.end();
+        }
+        if last < pattern.len() {
+            parts.push(Part::Literal(pattern[last..].to_string()));
+        }
+
+        Ok(Pattern { parts })
+    }
+
+    /// Placeholder names in order of appearance.
+    pub fn placeholders(&self) -> Vec<&str> {
+        self.parts
+            .iter()
+            .filter_map(|x| match x {
+                Part::Placeholder(name) => Some(name.as_str()),
+                Part::Literal(_) => None,
+            })
+            .collect()
+    }
+
+    /// Regex matching the whole pattern, each placeholder is captured by its name.
+    pub fn regex(&self, rules: &BTreeMap<String, String>) -> Result<Regex> {
+        let body = self
+            .parts
+            .iter()
+            .map(|x| match x {
+                Part::Literal(literal) => regex::escape(literal),
+                Part::Placeholder(name) => format!(
+                    "(?P<{}>{})",
+                    name,
+                    rules.get(name).map(|x| x.as_str()).unwrap_or(DEFAULT_RULE)
+                ),
+            })
+            .collect::<String>();
+        Ok(Regex::new(&format!("^{}$", body))?)
+    }
+
+    /// Values of all placeholders, None if name does not match the pattern.
+    pub fn captures(
+        &self,
+        rules: &BTreeMap<String, String>,
+        name: &str,
+    ) -> Result<Option<HashMap<String, String>>> {
+        let regex = self.regex(rules)?;
+        Ok(regex.captures(name).map(|captures| {
+            self.placeholders()
+                .into_iter()
+                .filter_map(|x| {
+                    captures
+                        .name(x)
+                        .map(|y| (x.to_string(), y.as_str().to_string()))
+                })
+                .collect()
+        }))
+    }
+
+    /// Replace placeholders with values, each value should match its rule.
+    pub fn fill(
+        &self,
+        rules: &BTreeMap<String, String>,
+        values: &HashMap<String, String>,
+    ) -> Result<String> {
+        let mut result = String::new();
+        for part in self.parts.iter() {
+            match part {
+                Part::Literal(literal) => result.push_str(literal),
+                Part::Placeholder(name) => {
+                    let value = match values.get(name) {
+                        None => bail!("missing value for {{{}}}", name),
+                        Some(value_v) => value_v,
+                    };
+                    if let Some(rule) = rules.get(name) {
+                        if !Regex::new(&format!("^(?:{})$", rule))?.is_match(value) {
+                            bail!("{} does not match {{{}}} rule {}", value, name, rule);
+                        }
+                    }
+                    result.push_str(value);
+                }
+            }
+        }
+        Ok(result)
+    }
+}
+
+/// Regex of a from or to branch name of config, {BASE} matches any version line.
+///
+/// from names are literal and match whole branch names, to names are regexes matching anywhere.
+pub fn branch_regex(name: &str, literal: bool) -> Result<Regex> {
+    let pattern = match literal {
+        true => format!(
+            "^{}$",
+            regex::escape(name).replace(&regex::escape(BASE_PLACEHOLDER), ".+")
+        ),
+        false => name.replace(BASE_PLACEHOLDER, ".+"),
+    };
+    Ok(Regex::new(&pattern)?)
+}
+
+/// Regex matching whole branch names only, e.g. dev does not match feature/dev-tools.
+pub fn whole_match_regex(regex: &str) -> Result<Regex> {
+    Ok(Regex::new(&format!("^(?:{})$", regex))?)
+}
+
+/// Replace every {KEY} in text with its value.
+pub fn replace_placeholders(text: &str, values: &HashMap<String, String>) -> String {
+    values.iter().fold(text.to_string(), |text, (key, value)| {
+        text.replace(&format!("{{{}}}", key), value)
+    })
+}
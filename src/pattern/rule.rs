@@ -0,0 +1,72 @@
+use anyhow::{bail, Result};
+use regex::Regex;
+
+use crate::config::definition::{NameRule, Normalize};
+
+/// Normalize name by rule, the ticket prefix is kept as is.
+pub fn normalize_name(name: &str, rule: &NameRule) -> Result<String> {
+    let normalize = match &rule.normalize {
+        None => return Ok(name.to_string()),
+        Some(normalize_v) => normalize_v,
+    };
+
+    let prefix_len = match &rule.ticket_prefix {
+        None => 0,
+        Some(ticket_prefix) => Regex::new(&format!("^(?:{})", ticket_prefix))?
+            .find(name)
+            .map(|x| x.end())
+            .unwrap_or(0),
+    };
+    let (prefix, rest) = name.split_at(prefix_len);
+
+    let rest = match normalize {
+        Normalize::Lowercase => rest.to_lowercase(),
+        Normalize::KebabCase => kebab_case(rest),
+    };
+    Ok(format!("{}{}", prefix, rest))
+}
+
+/// Validate name by rule.
+pub fn validate_name(name: &str, rule: &NameRule) -> Result<()> {
+    if let Some(max_length) = rule.max_length {
+        if name.chars().count() > max_length {
+            bail!("name {} is longer than {} characters", name, max_length);
+        }
+    }
+
+    if let Some(ticket_prefix) = &rule.ticket_prefix {
+        if !Regex::new(&format!("^(?:{})", ticket_prefix))?.is_match(name) {
+            bail!("name {} does not start with ticket {}", name, ticket_prefix);
+        }
+    }
+
+    if let Some(allowed) = &rule.allowed {
+        if !Regex::new(&format!("^(?:{})$", allowed))?.is_match(name) {
+            bail!("name {} does not match {}", name, allowed);
+        }
+    }
+
+    Ok(())
+}
+
+/// `My Feature!` and `myFeature` become `my-feature`, `/` is kept.
+fn kebab_case(text: &str) -> String {
+    let mut result = String::new();
+    let mut prev: Option<char> = None;
+    for c in text.chars() {
+        if c == '/' {
+            result.push(c);
+        } else if c.is_alphanumeric() {
+            let boundary = prev.is_some_and(|x| {
+                (!x.is_alphanumeric() && x != '/')
+                    || (c.is_uppercase() && (x.is_lowercase() || x.is_ascii_digit()))
+            });
+            if boundary && !result.is_empty() && !result.ends_with('/') {
+                result.push('-');
+            }
+            result.extend(c.to_lowercase());
+        }
+        prev = Some(c);
+    }
+    result
+}
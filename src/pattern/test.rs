@@ -0,0 +1,107 @@
+use super::*;
+use crate::config::definition::{NameRule, Normalize};
+
+fn rules() -> BTreeMap<String, String> {
+    BTreeMap::from([("TICKET".to_string(), "[A-Z]+-\\d+".to_string())])
+}
+
+#[test]
+fn parse_t() {
+    let pattern = Pattern::parse("{USER}/feature/{TICKET}-{NAME}").unwrap();
+    assert_eq!(pattern.placeholders(), vec!["USER", "TICKET", "NAME"]);
+    assert!(Pattern::parse("feature/{NAME}-{NAME}").is_err());
+}
+
+#[test]
+fn captures_t() {
+    let pattern = Pattern::parse("feature/{TICKET}-{NAME}").unwrap();
+    let values = pattern
+        .captures(&rules(), "feature/ABC-12-login-page")
+        .unwrap()
+        .unwrap();
+    assert_eq!(values["TICKET"], "ABC-12");
+    assert_eq!(values["NAME"], "login-page");
+    assert!(pattern
+        .captures(&rules(), "feature/login-page")
+        .unwrap()
+        .is_none());
+}
+
+#[test]
+fn fill_t() {
+    let pattern = Pattern::parse("feature/{TICKET}-{NAME}").unwrap();
+    let mut values = HashMap::from([("NAME".to_string(), "login".to_string())]);
+    assert!(pattern.fill(&rules(), &values).is_err());
+
+    values.insert("TICKET".to_string(), "abc".to_string());
+    assert!(pattern.fill(&rules(), &values).is_err());
+
+    values.insert("TICKET".to_string(), "ABC-12".to_string());
+    assert_eq!(
+        pattern.fill(&rules(), &values).unwrap(),
+        "feature/ABC-12-login"
+    );
+}
+
+#[test]
+fn normalize_name_t() {
+    let mut rule = NameRule {
+        normalize: Some(Normalize::KebabCase),
+        ..Default::default()
+    };
+    assert_eq!(normalize_name("My Feature!", &rule).unwrap(), "my-feature");
+    assert_eq!(
+        normalize_name("ui/darkMode", &rule).unwrap(),
+        "ui/dark-mode"
+    );
+
+    rule.ticket_prefix = Some("[A-Z]+-\\d+-".to_string());
+    assert_eq!(
+        normalize_name("ABC-12-Login Page", &rule).unwrap(),
+        "ABC-12-login-page"
+    );
+}
+
+#[test]
+fn validate_name_t() {
+    let rule = NameRule {
+        allowed: Some("[A-Za-z0-9-]+".to_string()),
+        max_length: Some(12),
+        ticket_prefix: Some("[A-Z]+-\\d+-".to_string()),
+        ..Default::default()
+    };
+    assert!(validate_name("ABC-12-login", &rule).is_ok());
+    assert!(validate_name("login", &rule).is_err());
+    assert!(validate_name("ABC-12-login-page", &rule).is_err());
+    assert!(validate_name("ABC-12-log_in", &rule).is_err());
+}
+
+#[test]
+fn regex_escape_t() {
+    let pattern = Pattern::parse("release/v{NAME}.x+(lts)").unwrap();
+    let regex = pattern.regex(&BTreeMap::new()).unwrap();
+    assert!(regex.is_match("release/v1.x+(lts)"));
+    assert!(!regex.is_match("release/v1-x+(lts)"));
+}
+
+#[test]
+fn branch_regex_t() {
+    let regex = branch_regex("support/{BASE}", true).unwrap();
+    assert!(regex.is_match("support/1.x"));
+    assert!(!regex.is_match("support/"));
+    assert!(!regex.is_match("old-support/1.x"));
+
+    let regex = branch_regex("support/{BASE}", false).unwrap();
+    assert!(regex.is_match("support/2.x"));
+    assert!(branch_regex("feature/*", false)
+        .unwrap()
+        .is_match("feature/a"));
+}
+
+#[test]
+fn whole_match_regex_t() {
+    let regex = whole_match_regex("dev|release/.*").unwrap();
+    assert!(regex.is_match("dev"));
+    assert!(regex.is_match("release/1.0"));
+    assert!(!regex.is_match("feature/dev-tools"));
+}
@@ -0,0 +1,40 @@
+use std::{collections::HashMap, fs, path::Path};
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+
+use super::TicketProvider;
+
+#[derive(Debug, Deserialize)]
+struct TicketEntry {
+    title: String,
+}
+
+/// Tickets of a local json or toml file like `{ "ABC-12": { "title": "Login page" } }`.
+pub struct FileProvider {
+    tickets: HashMap<String, TicketEntry>,
+}
+
+impl FileProvider {
+    pub fn load(path: &Path) -> Result<FileProvider> {
+        let text = fs::read_to_string(path)
+            .with_context(|| format!("unable to read tickets file {}", path.display()))?;
+        let json = path.extension().is_some_and(|x| x == "json");
+        FileProvider::parse(&text, json)
+            .with_context(|| format!("invalid tickets file {}", path.display()))
+    }
+
+    pub fn parse(text: &str, json: bool) -> Result<FileProvider> {
+        let tickets = match json {
+            true => serde_json::from_str(text)?,
+            false => toml::from_str(text)?,
+        };
+        Ok(FileProvider { tickets })
+    }
+}
+
+impl TicketProvider for FileProvider {
+    fn title(&self, id: &str) -> Result<Option<String>> {
+        Ok(self.tickets.get(id).map(|x| x.title.clone()))
+    }
+}
@@ -0,0 +1,102 @@
+use std::path::PathBuf;
+
+use anyhow::Result;
+use regex::Regex;
+
+use crate::{
+    config::definition::{BranchType, Config, TicketRule},
+    git::Git,
+};
+
+#[cfg(test)]
+mod test;
+
+mod file;
+
+pub use file::FileProvider;
+
+/// Resolve ticket titles from an issue tracker.
+pub trait TicketProvider {
+    /// None if the ticket is unknown
+    fn title(&self, id: &str) -> Result<Option<String>>;
+}
+
+/// Git config key of the ticket ID given on start.
+pub fn ticket_config_key(branch_name: &str) -> String {
+    format!("branch.{}.git-flow-ticket", branch_name)
+}
+
+/// Ticket ID given on start, or the first match of rule in branch name.
+pub fn get_ticket(branch_name: &str, rule: &TicketRule) -> Result<Option<String>> {
+    if let Some(ticket) = Git::get_config(&ticket_config_key(branch_name))? {
+        return Ok(Some(ticket));
+    }
+    find_ticket(branch_name, rule)
+}
+
+/// The first match of rule in text.
+pub fn find_ticket(text: &str, rule: &TicketRule) -> Result<Option<String>> {
+    let regex = Regex::new(&rule.pattern)?;
+    Ok(regex.find(text).map(|x| x.as_str().to_string()))
+}
+
+/// Whether ticket is exactly a ticket ID of rule.
+pub fn is_ticket(ticket: &str, rule: &TicketRule) -> Result<bool> {
+    let regex = Regex::new(&format!("^(?:{})$", rule.pattern))?;
+    Ok(regex.is_match(ticket))
+}
+
+/// Provider of config, None if no provider is configured.
+pub fn get_provider(config: &Config) -> Result<Option<Box<dyn TicketProvider>>> {
+    match &config.tickets.file {
+        None => Ok(None),
+        Some(file) => {
+            let path = Git::get_root_dir()
+                .unwrap_or_else(|_| PathBuf::from("."))
+                .join(file);
+            Ok(Some(Box::new(FileProvider::load(&path)?)))
+        }
+    }
+}
+
+/// Ticket of a task branch, None if branch type links no ticket or no ticket is found.
+pub fn get_branch_ticket(
+    branch_name: &str,
+    branch_type: &BranchType,
+    config: &Config,
+) -> Result<Option<Ticket>> {
+    let rule = match &branch_type.ticket {
+        None => return Ok(None),
+        Some(rule_v) => rule_v,
+    };
+    let id = match get_ticket(branch_name, rule)? {
+        None => return Ok(None),
+        Some(id_v) => id_v,
+    };
+
+    let provider = get_provider(config)?;
+    let title = match provider {
+        None => None,
+        Some(provider_v) => provider_v.title(&id)?,
+    };
+    Ok(Some(Ticket { id, title }))
+}
+
+pub struct Ticket {
+    pub id: String,
+    pub title: Option<String>,
+}
+
+impl Ticket {
+    /// e.g. `[ABC-12] Merge branch 'feature/login' into dev`, with the title in body
+    pub fn merge_message(&self, source_branch: &str, target_branch: &str) -> String {
+        let subject = format!(
+            "[{}] Merge branch '{}' into {}",
+            self.id, source_branch, target_branch
+        );
+        match &self.title {
+            None => subject,
+            Some(title) => format!("{}\n\n{} {}", subject, self.id, title),
+        }
+    }
+}
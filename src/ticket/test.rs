@@ -0,0 +1,51 @@
+use super::*;
+
+fn rule() -> TicketRule {
+    TicketRule {
+        pattern: "[A-Z]+-\\d+".to_string(),
+        required: true,
+    }
+}
+
+#[test]
+fn find_ticket_t() {
+    assert_eq!(
+        find_ticket("feature/ABC-12-login", &rule()).unwrap(),
+        Some("ABC-12".to_string())
+    );
+    assert_eq!(find_ticket("feature/login", &rule()).unwrap(), None);
+}
+
+#[test]
+fn is_ticket_t() {
+    assert!(is_ticket("ABC-12", &rule()).unwrap());
+    assert!(!is_ticket("ABC-12-login", &rule()).unwrap());
+}
+
+#[test]
+fn file_provider_t() {
+    let provider = FileProvider::parse(r#"{ "ABC-12": { "title": "Login page" } }"#, true).unwrap();
+    assert_eq!(
+        provider.title("ABC-12").unwrap(),
+        Some("Login page".to_string())
+    );
+    assert_eq!(provider.title("ABC-13").unwrap(), None);
+
+    let provider = FileProvider::parse("[ABC-12]\ntitle = \"Login page\"\n", false).unwrap();
+    assert_eq!(
+        provider.title("ABC-12").unwrap(),
+        Some("Login page".to_string())
+    );
+}
+
+#[test]
+fn merge_message_t() {
+    let ticket = Ticket {
+        id: "ABC-12".to_string(),
+        title: Some("Login page".to_string()),
+    };
+    assert_eq!(
+        ticket.merge_message("feature/login", "dev"),
+        "[ABC-12] Merge branch 'feature/login' into dev\n\nABC-12 Login page"
+    );
+}
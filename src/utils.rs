@@ -1,17 +1,31 @@
-use std::{path::PathBuf, process};
+use std::{
+    cmp::Ordering,
+    collections::HashMap,
+    io::{self, Write},
+    path::PathBuf,
+    process,
+};
 
-use anyhow::{bail, Result};
+use anyhow::{anyhow, bail, Result};
 use regex::Regex;
 
 use crate::{
     config::{
-        definition::{BranchType, Command, BRANCH_NAME_PLACEHOLDER},
+        definition::{
+            BranchType, Command, TargetBranch, VersionBump, BASE_PLACEHOLDER, NAME_KEY,
+            VERSION_PLACEHOLDER,
+        },
         read::read_config,
     },
     echo::Echo,
-    git::Git,
+    git::{Git, PushStatus},
+    journal::Journal,
+    pattern::{normalize_name, replace_placeholders, Pattern},
+    version::{bump_version_files, compare_versions},
 };
 
+const DEFAULT_BUMP_MESSAGE: &str = "chore: bump version to {VERSION}";
+
 pub fn env_valid() -> bool {
     if !Git::git_installed() {
         Echo::error("git command is not found");
@@ -26,9 +40,177 @@ pub fn env_valid() -> bool {
     true
 }
 
+/// Check the working tree before switching branches.
+///
+/// Returns whether changes have been stashed.
+pub fn stash_changes(autostash: bool) -> Result<bool> {
+    let dirty_files = match Git::get_dirty_files() {
+        Err(err) => {
+            Echo::error(err.to_string());
+            bail!("");
+        }
+        Ok(dirty_files_v) => dirty_files_v,
+    };
+    if dirty_files.is_empty() {
+        return Ok(false);
+    }
+
+    if !autostash {
+        Echo::error(format!(
+            "working tree is not clean:\n{}",
+            dirty_files.join("\n")
+        ));
+        Echo::info("commit or stash these changes first, or run with --autostash");
+        bail!("");
+    }
+
+    let finish = Echo::progress("stash changes");
+    match Git::stash_push("git-flow autostash") {
+        Err(err) => {
+            finish(false, &err.to_string());
+            bail!("");
+        }
+        Ok(_) => finish(true, "stash changes"),
+    }
+    Ok(true)
+}
+
+/// Restore stashed changes, on `branch` if it still exists, otherwise on the current branch.
+/// The stash is kept if the flow stopped with changes or in the middle of a merge or rebase.
+pub fn pop_stash(branch: Option<&str>) {
+    let clean = Git::get_dirty_files().is_ok_and(|x| x.is_empty());
+    if !clean || Git::is_operation_in_progress().unwrap_or(true) {
+        Echo::warning("working tree is not clean, changes are kept in stash@{0}");
+        return;
+    }
+
+    if let Some(branch_v) = branch {
+        let branch_exists =
+            Git::get_local_branches().is_ok_and(|x| x.iter().any(|y| y == branch_v));
+        let switched = Git::get_current_branch().is_ok_and(|x| x == branch_v);
+        if branch_exists && !switched {
+            let finish = Echo::progress(format!("switch to branch {}", branch_v));
+            match Git::switch(branch_v) {
+                Err(err) => finish(false, &err.to_string()),
+                Ok(_) => finish(true, &format!("switch to branch {}", branch_v)),
+            }
+        }
+    }
+
+    let finish = Echo::progress("restore stashed changes");
+    match Git::stash_pop() {
+        Err(err) => finish(false, &err.to_string()),
+        Ok(_) => finish(true, "restore stashed changes"),
+    }
+}
+
+/// Push refspecs of branches in a single push, with the result of each branch.
+pub fn push_refspecs(
+    repo: &str,
+    branches: &[String],
+    refspecs: &[String],
+    leases: &[String],
+) -> Vec<(String, Result<()>)> {
+    if branches.is_empty() {
+        return Vec::new();
+    }
+
+    let statuses = Git::push_refspecs(repo, refspecs, leases);
+    branches
+        .iter()
+        .map(|branch| {
+            let result = match &statuses {
+                Err(err) => Err(anyhow!(err.to_string())),
+                Ok(statuses_v) => match statuses_v.iter().find(|x| &x.branch == branch) {
+                    None => Err(anyhow!("no result reported by git push")),
+                    Some(PushStatus { error: None, .. }) => Ok(()),
+                    Some(PushStatus {
+                        error: Some(error), ..
+                    }) => Err(anyhow!(error.to_string())),
+                },
+            };
+            (branch.to_string(), result)
+        })
+        .collect()
+}
+
+/// Print the succeeded branches of a step, and the reason of each failed one.
+pub fn report_results(
+    finish: impl FnOnce(bool, &str),
+    step: &str,
+    results: &[(String, Result<()>)],
+) {
+    let succeeded = results
+        .iter()
+        .filter(|(_, x)| x.is_ok())
+        .map(|(x, _)| x.to_string())
+        .collect::<Vec<String>>();
+    let failed = results
+        .iter()
+        .filter_map(|(x, y)| y.as_ref().err().map(|z| (x, z)))
+        .collect::<Vec<(&String, &anyhow::Error)>>();
+
+    if failed.is_empty() {
+        finish(true, &format!("\r{}: {}", step, succeeded.join(", ")));
+        return;
+    }
+
+    finish(
+        false,
+        &format!(
+            "{} failed on {} of {} branches",
+            step,
+            failed.len(),
+            results.len()
+        ),
+    );
+    for (branch, err) in failed {
+        Echo::error(format!(
+            "{}: {}",
+            branch,
+            err.to_string().lines().next().unwrap_or_default()
+        ));
+    }
+    if !succeeded.is_empty() {
+        Echo::success(format!("{}: {}", step, succeeded.join(", ")));
+    }
+}
+
+/// Ask the user to confirm, default is no.
+pub fn confirm(msg: &str) -> bool {
+    print!("{} [y/N] ", msg);
+    if io::stdout().flush().is_err() {
+        return false;
+    }
+
+    let mut input = String::new();
+    if io::stdin().read_line(&mut input).is_err() {
+        return false;
+    }
+    matches!(input.trim().to_lowercase().as_str(), "y" | "yes")
+}
+
+/// Ask the user for a value, empty input is an error.
+pub fn prompt(msg: &str) -> Result<String> {
+    print!("{}: ", msg);
+    io::stdout().flush()?;
+
+    let mut input = String::new();
+    io::stdin().read_line(&mut input)?;
+    let input = input.trim().to_string();
+    if input.is_empty() {
+        bail!("no value for {}", msg);
+    }
+    Ok(input)
+}
+
+/// vars are KEY=VALUE pairs filling placeholders of create other than {NAME}.
+/// existing is set for commands on an existing branch, whose missing values may be taken from it.
 pub fn get_branch_type_name(
     branch_name: String,
     branch_type: Option<String>,
+    vars: Vec<String>,
+    existing: bool,
     config_path: Option<PathBuf>,
 ) -> Result<(
     /* branch_name */ String,
@@ -45,27 +227,215 @@ pub fn get_branch_type_name(
             None => bail!("no matched branch type"),
             Some(target_branch_type_v) => {
                 return Ok((
-                    target_branch_type_v
-                        .create
-                        .replace(BRANCH_NAME_PLACEHOLDER, &branch_name),
+                    fill_create(&branch_name, target_branch_type_v, vars, existing)?,
                     target_branch_type_v.clone(),
                 ))
             }
         }
     }
 
-    let target_branch_type = config.branch_types.iter().find(|x| {
+    let target_branch_types = config
+        .branch_types
+        .iter()
+        .filter(|x| create_regex(x).is_ok_and(|y| y.is_match(&branch_name)))
+        .collect::<Vec<&BranchType>>();
+    match target_branch_types.len() {
+        0 => bail!("no matched branch type"),
+        1 => Ok((branch_name, target_branch_types[0].clone())),
+        _ => bail!(
+            "branch {} matches branch types {}, please specify one",
+            branch_name,
+            target_branch_types
+                .iter()
+                .map(|x| x.name.as_str())
+                .collect::<Vec<&str>>()
+                .join(", ")
+        ),
+    }
+}
+
+/// Fill create of branch_type with name and vars.
+///
+/// Missing values are prompted, unless existing is set and only one existing branch matches the given values.
+fn fill_create(
+    name: &str,
+    branch_type: &BranchType,
+    vars: Vec<String>,
+    existing: bool,
+) -> Result<String> {
+    let pattern = Pattern::parse(&branch_type.create)?;
+
+    let name = normalize_name(name, &branch_type.name_rule)?;
+    let mut values = HashMap::from([(NAME_KEY.to_string(), name)]);
+    for var in vars.iter() {
+        match var.split_once('=') {
+            None => bail!("invalid var {}, expected KEY=VALUE", var),
+            Some((key, value)) => {
+                if !pattern.placeholders().contains(&key) {
+                    bail!("{{{}}} is not found in {}", key, &branch_type.create);
+                }
+                values.insert(key.to_string(), value.to_string());
+            }
+        }
+    }
+
+    let missing = pattern
+        .placeholders()
+        .into_iter()
+        .filter(|x| !values.contains_key(*x))
+        .map(|x| x.to_string())
+        .collect::<Vec<String>>();
+    if missing.is_empty() {
+        return pattern.fill(&branch_type.placeholders, &values);
+    }
+
+    // -- find existing branch --
+    let mut matched = Vec::<HashMap<String, String>>::new();
+    let branches = match existing {
+        true => Git::get_local_branches().unwrap_or_default(),
+        false => Vec::new(),
+    };
+    for branch in branches {
+        if let Some(captures) = pattern.captures(&branch_type.placeholders, &branch)? {
+            if values
+                .iter()
+                .all(|(key, value)| captures.get(key) == Some(value))
+            {
+                matched.push(captures);
+            }
+        }
+    }
+    if matched.len() == 1 {
+        return pattern.fill(&branch_type.placeholders, &matched[0]);
+    }
+
+    // -- prompt --
+    for key in missing {
+        let value = prompt(&format!("{{{}}}", key))?;
+        values.insert(key, value);
+    }
+    pattern.fill(&branch_type.placeholders, &values)
+}
+
+/// Value of {NAME} in branch_name, or branch_name itself if it does not match create.
+pub fn get_task_name(branch_name: &str, branch_type: &BranchType) -> String {
+    Pattern::parse(&branch_type.create)
+        .and_then(|x| x.captures(&branch_type.placeholders, branch_name))
+        .ok()
+        .flatten()
+        .and_then(|mut x| x.remove(NAME_KEY))
+        .unwrap_or(branch_name.to_string())
+}
+
+/// Switch to the task branch, then commit version files bumped to {NAME}.
+pub fn bump_task_version(
+    branch_name: &str,
+    branch_type: &BranchType,
+    version_bump: &VersionBump,
+) -> Result<()> {
+    let version = get_task_name(branch_name, branch_type);
+    let msg = format!("bump version to {}", &version);
+    let finish = Echo::progress(&msg);
+    let before = Git::get_commit(branch_name).ok();
+
+    let result = Git::switch(branch_name)
+        .and_then(|_| bump_version_files(&version_bump.files, &version))
+        .and_then(|files| {
+            let message = version_bump
+                .message
+                .as_deref()
+                .unwrap_or(DEFAULT_BUMP_MESSAGE)
+                .replace(VERSION_PLACEHOLDER, &version);
+            Git::commit_files(
+                &files.iter().map(|x| x.as_str()).collect::<Vec<&str>>(),
+                &message,
+            )
+        });
+    Journal::record_branch(branch_name, before);
+
+    match result {
+        Err(err) => {
+            finish(false, &err.to_string());
+            bail!("");
+        }
+        Ok(_) => {
+            finish(true, &msg);
+            Ok(())
+        }
+    }
+}
+
+/// Git config key of the base a task branch is started on.
+pub fn base_config_key(branch_name: &str) -> String {
+    format!("branch.{}.git-flow-base", branch_name)
+}
+
+/// Replace {BASE} in from and to with base, which defaults to the one recorded on start.
+///
+/// Also returns the newer lines matching from to forward port to, if enabled.
+pub fn resolve_base(
+    branch_name: &str,
+    branch_type: BranchType,
+    base: Option<String>,
+) -> Result<(BranchType, Vec<String>)> {
+    let has_base = branch_type.from.contains(BASE_PLACEHOLDER)
+        || branch_type
+            .to
+            .iter()
+            .any(|x| x.name.contains(BASE_PLACEHOLDER));
+    if !has_base {
+        if base.is_some() {
+            bail!(
+                "branch type {} has no {} placeholder",
+                branch_type.name,
+                BASE_PLACEHOLDER
+            );
+        }
+        return Ok((branch_type, Vec::new()));
+    }
+
+    let base = match base {
+        Some(base_v) => base_v,
+        None => match Git::get_config(&base_config_key(branch_name))? {
+            Some(base_v) => base_v,
+            None => bail!("branch type {} requires --base", branch_type.name),
+        },
+    };
+
+    // -- resolve from and to --
+    let mut resolved_branch_type = branch_type.clone();
+    resolved_branch_type.from = branch_type.from.replace(BASE_PLACEHOLDER, &base);
+    resolved_branch_type.to = branch_type
+        .to
+        .iter()
+        .map(|x| TargetBranch {
+            name: x.name.replace(BASE_PLACEHOLDER, &regex::escape(&base)),
+            ..x.clone()
+        })
+        .collect();
+
+    // -- find newer lines --
+    let mut forward_lines = Vec::<String>::new();
+    if branch_type.forward_port && branch_type.from.contains(BASE_PLACEHOLDER) {
         let regex = Regex::new(&format!(
             "^{}$",
-            x.create.replace(BRANCH_NAME_PLACEHOLDER, ".*")
-        ))
-        .unwrap();
-        regex.is_match(&branch_name)
-    });
-    match target_branch_type {
-        None => bail!("no matched branch type"),
-        Some(target_branch_type_v) => Ok((branch_name, target_branch_type_v.clone())),
+            regex::escape(&branch_type.from).replace(&regex::escape(BASE_PLACEHOLDER), "(.+)")
+        ))?;
+        for branch in Git::get_local_branches()? {
+            let version = regex.captures(&branch).and_then(|x| x.get(1));
+            if version.is_some_and(|x| compare_versions(x.as_str(), &base) == Ordering::Greater) {
+                forward_lines.push(branch);
+            }
+        }
+        forward_lines.sort_by(|a, b| compare_versions(a, b));
     }
+
+    Ok((resolved_branch_type, forward_lines))
+}
+
+/// Regex matching branch names created by create.
+pub fn create_regex(branch_type: &BranchType) -> Result<Regex> {
+    Pattern::parse(&branch_type.create)?.regex(&branch_type.placeholders)
 }
 
 pub fn run_hook(
@@ -79,20 +449,16 @@ pub fn run_hook(
     };
 
     // -- map args --
-    let regex = Regex::new(&branch_type.create.replace(BRANCH_NAME_PLACEHOLDER, "(.*)")).unwrap();
-    let short_branch_name = match regex.captures(branch_name) {
-        None => None,
-        Some(captures) => captures.get(1),
-    };
-    let args = match short_branch_name {
+    let values = Pattern::parse(&branch_type.create)
+        .and_then(|x| x.captures(&branch_type.placeholders, branch_name))
+        .ok()
+        .flatten();
+    let args = match values {
         None => command.args,
-        Some(name) => command
+        Some(values_v) => command
             .args
             .iter()
-            .map(|x| {
-                x.replace(BRANCH_NAME_PLACEHOLDER, name.as_str())
-                    .to_string()
-            })
+            .map(|x| replace_placeholders(x, &values_v))
             .collect::<Vec<String>>(),
     };
 
@@ -0,0 +1,97 @@
+use std::{cmp::Ordering, fs, path::Path};
+
+use anyhow::{bail, Result};
+use regex::Regex;
+use toml_edit::{DocumentMut, Value};
+
+use crate::{config::definition::VersionFile, git::Git};
+
+#[cfg(test)]
+mod test;
+
+/// Compare versions by their numeric parts, e.g. 1.x < 1.2 < 2.0 < 10.x.
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    numeric_parts(a).cmp(&numeric_parts(b))
+}
+
+fn numeric_parts(version: &str) -> Vec<u64> {
+    let regex = Regex::new(r"\d+").unwrap();
+    regex
+        .find_iter(version)
+        .filter_map(|x| x.as_str().parse::<u64>().ok())
+        .collect()
+}
+
+/// Write version to files, returns their paths relative to the git root.
+pub fn bump_version_files(files: &[VersionFile], version: &str) -> Result<Vec<String>> {
+    let root_dir = Git::get_root_dir()?;
+    for file in files.iter() {
+        let path = root_dir.join(&file.path);
+        let text = fs::read_to_string(&path)?;
+        fs::write(
+            &path,
+            bump_version(&path, &text, file.pattern.as_deref(), version)?,
+        )?;
+    }
+    Ok(files.iter().map(|x| x.path.clone()).collect())
+}
+
+/// Replace the version in text of file at path.
+///
+/// Cargo.toml, package.json and pyproject.toml are known, other files require a pattern whose first group is the version.
+pub fn bump_version(
+    path: &Path,
+    text: &str,
+    pattern: Option<&str>,
+    version: &str,
+) -> Result<String> {
+    if let Some(pattern_v) = pattern {
+        return replace_first_group(text, pattern_v, version);
+    }
+
+    let file_name = path
+        .file_name()
+        .and_then(|x| x.to_str())
+        .unwrap_or_default();
+    match file_name {
+        "Cargo.toml" => set_toml_version(text, &[&["package"], &["workspace", "package"]], version),
+        "pyproject.toml" => set_toml_version(text, &[&["project"], &["tool", "poetry"]], version),
+        // the first version field is the one of the package
+        "package.json" => replace_first_group(text, r#""version"\s*:\s*"([^"]*)""#, version),
+        _ => bail!("pattern is required to bump version of {}", path.display()),
+    }
+}
+
+/// Set version of the first existing table of tables, formatting and comments are kept.
+fn set_toml_version(text: &str, tables: &[&[&str]], version: &str) -> Result<String> {
+    let mut document = text.parse::<DocumentMut>()?;
+    for keys in tables.iter() {
+        let item = keys
+            .iter()
+            .try_fold(document.as_item_mut(), |item, key| item.get_mut(key));
+        let field = item
+            .and_then(|x| x.get_mut("version"))
+            .and_then(|x| x.as_value_mut());
+        if let Some(field_v) = field {
+            let decor = field_v.decor().clone();
+            *field_v = Value::from(version);
+            *field_v.decor_mut() = decor;
+            return Ok(document.to_string());
+        }
+    }
+    bail!("no version field is found")
+}
+
+fn replace_first_group(text: &str, pattern: &str, version: &str) -> Result<String> {
+    let regex = Regex::new(pattern)?;
+    let group = match regex.captures(text).and_then(|x| x.get(1)) {
+        None => bail!("{} does not match any version", pattern),
+        Some(group_v) => group_v,
+    };
+    Ok(format!(
+        "{}{}{}",
+        &text[..group.start()],
+        version,
+        &text[group.end()..]
+    ))
+}
@@ -0,0 +1,45 @@
+use super::*;
+
+#[test]
+fn compare_versions_t() {
+    assert_eq!(compare_versions("1.x", "2.x"), Ordering::Less);
+    assert_eq!(compare_versions("2.10", "2.9"), Ordering::Greater);
+    assert_eq!(compare_versions("1.2", "1.x"), Ordering::Greater);
+    assert_eq!(compare_versions("v1.0", "1.0"), Ordering::Equal);
+}
+
+#[test]
+fn bump_version_t() {
+    let bump = |name: &str, text: &str, pattern: Option<&str>| {
+        bump_version(Path::new(name), text, pattern, "1.2.0").unwrap()
+    };
+
+    // not named Cargo.toml, which cargo package would take as a nested package
+    let cargo = bump(
+        "Cargo.toml",
+        include_str!("fixtures/Cargo.toml.fixture"),
+        None,
+    );
+    assert!(cargo.contains("# keep comments\nversion = \"1.2.0\""));
+    assert!(cargo.contains("serde = { version = \"1.0\" }"));
+
+    let package = bump("package.json", include_str!("fixtures/package.json"), None);
+    assert!(package.contains("\"version\": \"1.2.0\""));
+    assert!(package.contains("\"left-pad\": \"1.3.0\""));
+
+    let pyproject = bump(
+        "pyproject.toml",
+        include_str!("fixtures/pyproject.toml"),
+        None,
+    );
+    assert!(pyproject.contains("version = \"1.2.0\""));
+
+    let text = bump(
+        "version.txt",
+        include_str!("fixtures/version.txt"),
+        Some("VERSION=(.*)"),
+    );
+    assert_eq!(text, "VERSION=1.2.0\n");
+
+    assert!(bump_version(Path::new("version.txt"), "", None, "1.2.0").is_err());
+}